@@ -0,0 +1,89 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Answer of a bot to one turn
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    pub lines: Vec<String>,
+    pub elapsed: Duration,
+    pub timed_out: bool,
+}
+
+/// Bot executable talking over piped stdin and stdout. Its stdout is read on
+/// a separate thread so a bot that hangs can be given up on.
+pub struct Bot {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Bot {
+    /// `cmd` is split on whitespace, e.g. `python3 bot.py`
+    pub fn spawn(cmd: &str, stderr: bool) -> io::Result<Self> {
+        let mut words = cmd.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty bot command"))?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(if stderr {
+                Stdio::inherit()
+            } else {
+                Stdio::null()
+            })
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line.trim_end().to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    /// Sends the input and collects up to `n` lines of reply before the
+    /// time limit, a crashed bot simply answers with fewer lines
+    pub fn play(&mut self, input: &str, n: usize, limit: Duration) -> Reply {
+        let start = Instant::now();
+        let mut reply = Reply::default();
+        if self.stdin.write_all(input.as_bytes()).is_err() || self.stdin.flush().is_err() {
+            return reply;
+        }
+        while reply.lines.len() < n {
+            let left = limit.saturating_sub(start.elapsed());
+            match self.lines.recv_timeout(left) {
+                Ok(line) => reply.lines.push(line),
+                Err(RecvTimeoutError::Timeout) => {
+                    reply.timed_out = true;
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        reply.elapsed = start.elapsed();
+        reply
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
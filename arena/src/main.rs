@@ -0,0 +1,170 @@
+//! Plays two bot executables against each other over a series of seeded
+//! games and reports the win rate of the first one.
+//!
+//! Every seed is played twice with the bots swapping sides, so neither gets
+//! the better start of an asymmetric map.
+
+mod bot;
+mod stats;
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::io;
+use std::process;
+use std::time::Duration;
+
+use bot::Bot;
+use referees::{Outcome, Referee};
+use stats::Summary;
+
+#[derive(Debug, Clone)]
+struct Options {
+    game: String,
+    bots: [String; 2],
+    games: u32,
+    seed: u64,
+    /// Extra time added to every turn, e.g. for debug builds
+    slack: Duration,
+    stderr: bool,
+}
+
+impl Options {
+    const USAGE: &'static str =
+        "usage: arena <game> <bot_a> <bot_b> [--games N] [--seed S] [--slack MS] [--stderr]";
+
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let [game, a, b, rest @ ..] = args else {
+            return Err(Self::USAGE.to_string());
+        };
+        if !referees::NAMES.contains(&game.as_str()) {
+            return Err(format!(
+                "unknown game {}, expected one of {}",
+                game,
+                referees::NAMES.join(", ")
+            ));
+        }
+        let mut options = Self {
+            game: game.clone(),
+            bots: [a.clone(), b.clone()],
+            games: 10,
+            seed: 0,
+            slack: Duration::ZERO,
+            stderr: false,
+        };
+        let mut rest = rest.iter();
+        while let Some(flag) = rest.next() {
+            if flag == "--stderr" {
+                options.stderr = true;
+                continue;
+            }
+            let value = rest
+                .next()
+                .ok_or_else(|| format!("missing value for {}", flag))?;
+            let number = value
+                .parse::<u64>()
+                .map_err(|_| format!("invalid value {} for {}", value, flag))?;
+            match flag.as_str() {
+                "--games" => options.games = number as u32,
+                "--seed" => options.seed = number,
+                "--slack" => options.slack = Duration::from_millis(number),
+                _ => return Err(format!("unknown option {}\n{}", flag, Self::USAGE)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Result of a single game, indexed by player
+#[derive(Debug, Clone)]
+struct Match {
+    outcome: Outcome,
+    summary: String,
+    timeouts: [u32; 2],
+    slowest: [Duration; 2],
+}
+
+/// Plays one game between two bot commands, `bots[0]` being player 0
+fn run_match(
+    mut referee: Box<dyn Referee>,
+    bots: [&str; 2],
+    slack: Duration,
+    stderr: bool,
+) -> io::Result<Match> {
+    let mut players = [Bot::spawn(bots[0], stderr)?, Bot::spawn(bots[1], stderr)?];
+    let mut inputs = [referee.initial_input(0), referee.initial_input(1)];
+    let mut first_turn = [true; 2];
+    let mut timeouts = [0; 2];
+    let mut slowest = [Duration::ZERO; 2];
+    while referee.outcome().is_none() {
+        let mut outputs = [Vec::new(), Vec::new()];
+        for (p, bot) in players.iter_mut().enumerate() {
+            let n = referee.expected_lines(p);
+            if n == 0 {
+                continue;
+            }
+            inputs[p].push_str(&referee.turn_input(p));
+            let limit = referee.time_limit(first_turn[p]) + slack;
+            let reply = bot.play(&inputs[p], n, limit);
+            if reply.timed_out {
+                timeouts[p] += 1;
+            }
+            if !first_turn[p] {
+                slowest[p] = slowest[p].max(reply.elapsed);
+            }
+            outputs[p] = reply.lines;
+            inputs[p].clear();
+            first_turn[p] = false;
+        }
+        referee.play_turn([&outputs[0], &outputs[1]]);
+    }
+    Ok(Match {
+        outcome: referee.outcome().unwrap(),
+        summary: referee.to_string(),
+        timeouts,
+        slowest,
+    })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let mut summary = Summary::default();
+    for i in 0..options.games {
+        let seed = options.seed + (i / 2) as u64;
+        let swapped = i % 2 == 1;
+        let (a, b) = (options.bots[0].as_str(), options.bots[1].as_str());
+        let bots = if swapped { [b, a] } else { [a, b] };
+        let referee = referees::referee(&options.game, seed).unwrap();
+        let result = match run_match(referee, bots, options.slack, options.stderr) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("failed to run game {}: {}", i, e);
+                process::exit(1);
+            }
+        };
+        // back to the point of view of the first bot
+        let (first, second) = if swapped { (1, 0) } else { (0, 1) };
+        let outcome = if swapped {
+            result.outcome.swapped()
+        } else {
+            result.outcome
+        };
+        summary.add(outcome);
+        summary.timeouts[0] += result.timeouts[first];
+        summary.timeouts[1] += result.timeouts[second];
+        summary.slowest[0] = summary.slowest[0].max(result.slowest[first]);
+        summary.slowest[1] = summary.slowest[1].max(result.slowest[second]);
+        println!(
+            "game {} seed {} | bot_a is p{} | {}",
+            i, seed, first, result.summary
+        );
+    }
+    println!("{}", summary);
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::time::Duration;
+
+use referees::Outcome;
+
+/// Results from the point of view of the first bot
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Turns each bot failed to answer in time
+    pub timeouts: [u32; 2],
+    /// Slowest answer of each bot after the first turn
+    pub slowest: [Duration; 2],
+}
+
+impl Summary {
+    /// z score of the two sided 95% confidence interval
+    const Z: f64 = 1.96;
+
+    /// `outcome` is given with the first bot as player 0
+    pub fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Winner(0) => self.wins += 1,
+            Outcome::Winner(_) => self.losses += 1,
+            Outcome::Draw => self.draws += 1,
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Share of the points won, a draw is worth half a win
+    pub fn win_rate(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 + self.draws as f64 * 0.5) / self.games() as f64
+    }
+
+    /// Wilson score interval of the win rate, which stays within `[0, 1]`
+    /// and is sensible for the handful of games a local run plays
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let n = self.games() as f64;
+        if n == 0.0 {
+            return (0.0, 1.0);
+        }
+        let p = self.win_rate();
+        let z2 = Self::Z * Self::Z;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let spread = Self::Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        ((center - spread).max(0.0), (center + spread).min(1.0))
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.confidence_interval();
+        writeln!(
+            f,
+            "{} games | wins {} losses {} draws {}",
+            self.games(),
+            self.wins,
+            self.losses,
+            self.draws
+        )?;
+        writeln!(
+            f,
+            "win rate {:.1}% (95% CI {:.1}% - {:.1}%)",
+            self.win_rate() * 100.0,
+            low * 100.0,
+            high * 100.0
+        )?;
+        write!(
+            f,
+            "timeouts {} / {} | slowest turn {:?} / {:?}",
+            self.timeouts[0], self.timeouts[1], self.slowest[0], self.slowest[1]
+        )
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use referees::Outcome;
+
+use crate::stats::Summary;
+use crate::*;
+
+#[test]
+fn summary_win_rate_and_interval() {
+    let mut summary = Summary::default();
+    assert_eq!(summary.confidence_interval(), (0.0, 1.0));
+    for _ in 0..6 {
+        summary.add(Outcome::Winner(0));
+    }
+    summary.add(Outcome::Winner(1));
+    summary.add(Outcome::Winner(1));
+    summary.add(Outcome::Draw);
+    summary.add(Outcome::Draw);
+    assert_eq!(summary.games(), 10);
+    assert!((summary.win_rate() - 0.7).abs() < 1e-9);
+    let (low, high) = summary.confidence_interval();
+    assert!((low - 0.397).abs() < 1e-3, "{}", low);
+    assert!((high - 0.892).abs() < 1e-3, "{}", high);
+    // more games, tighter interval
+    let mut more = summary.clone();
+    for _ in 0..9 {
+        for o in [Outcome::Winner(0), Outcome::Winner(0), Outcome::Winner(1)] {
+            more.add(o);
+        }
+    }
+    let (l, h) = more.confidence_interval();
+    assert!(h - l < high - low);
+}
+
+#[test]
+fn options_parse() {
+    let args = ["fall2022", "./a", "./b", "--games", "4", "--slack", "20"].map(String::from);
+    let options = Options::parse(&args).unwrap();
+    assert_eq!(options.games, 4);
+    assert_eq!(options.seed, 0);
+    assert_eq!(options.slack, Duration::from_millis(20));
+    assert!(Options::parse(&args[..2]).is_err());
+    let args = ["chess", "./a", "./b"].map(String::from);
+    assert!(Options::parse(&args).is_err());
+    let args = ["fall2022", "./a", "./b", "--games"].map(String::from);
+    assert!(Options::parse(&args).is_err());
+}
+
+#[test]
+fn bot_timeout_and_crash() {
+    let mut bot = bot::Bot::spawn("sleep 5", false).unwrap();
+    let reply = bot.play("1\n", 1, Duration::from_millis(50));
+    assert!(reply.timed_out);
+    assert!(reply.lines.is_empty());
+    let mut bot = bot::Bot::spawn("true", false).unwrap();
+    let reply = bot.play("1\n", 1, Duration::from_millis(500));
+    assert!(!reply.timed_out);
+    assert!(reply.lines.is_empty());
+    // a bot echoing its input answers in time
+    let mut bot = bot::Bot::spawn("cat", false).unwrap();
+    let reply = bot.play("WAIT\nWAIT\n", 2, Duration::from_millis(500));
+    assert_eq!(reply.lines, ["WAIT", "WAIT"]);
+}
+
+#[test]
+fn crashed_bot_loses_the_match() {
+    // yes keeps answering WAIT, a valid command of both games
+    let referee = referees::referee("spring2022", 0).unwrap();
+    let result = run_match(referee, ["true", "yes WAIT"], Duration::ZERO, false).unwrap();
+    assert_eq!(result.outcome, Outcome::Winner(1));
+    let referee = referees::referee("spring2021", 0).unwrap();
+    let result = run_match(referee, ["sleep 5", "yes WAIT"], Duration::ZERO, false).unwrap();
+    assert_eq!(result.timeouts, [1, 0]);
+    assert_eq!(result.outcome, Outcome::Winner(1));
+}
@@ -0,0 +1,382 @@
+//! Flattening of a crate and its path dependencies into a single file.
+//!
+//! `mod name;` declarations are replaced by inline `mod name { .. }` blocks
+//! with the content of the module file, the modules built for the tests
+//! only (`#[cfg(test)]` or `#[cfg(all(test, ..))]`) are dropped.
+//! Path dependencies become modules at the end of the file, so their paths
+//! are rewritten: `dep::x` to `crate::dep::x` in the dependents and
+//! `crate::x` to `crate::dep::x` in the dependency itself.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::manifest::Manifest;
+
+/// Maximum source size accepted by CodinGame, in characters
+pub const SIZE_LIMIT: usize = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub source: String,
+    pub edition: String,
+    /// Files inlined, the crate roots included
+    pub files: usize,
+}
+
+/// Path prefixes to rewrite, e.g. `("crate", "crate::common")`
+type Renames = Vec<(String, String)>;
+
+pub fn bundle(dir: &Path) -> io::Result<Bundle> {
+    let top = Manifest::read(dir)?;
+    let mut libs = BTreeMap::new();
+    collect_libs(&top, &mut libs)?;
+    let mut bundle = Bundle {
+        source: String::new(),
+        edition: top.edition.clone(),
+        files: 0,
+    };
+    bundle.expand(&top.root(false), &dependency_renames(&top))?;
+    for (name, lib) in libs.iter() {
+        let mut renames = dependency_renames(lib);
+        renames.push(("crate".to_string(), format!("crate::{}", name)));
+        bundle
+            .source
+            .push_str(&format!("\n#[allow(dead_code)]\npub mod {} {{\n", name));
+        bundle.expand(&lib.root(true), &renames)?;
+        bundle.source.push_str("}\n");
+    }
+    Ok(bundle)
+}
+
+/// Path dependencies of `manifest`, transitively
+fn collect_libs(manifest: &Manifest, libs: &mut BTreeMap<String, Manifest>) -> io::Result<()> {
+    if let Some(dep) = manifest.other_deps.first() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{} depends on {}, which is not a path dependency",
+                manifest.name, dep
+            ),
+        ));
+    }
+    for (name, path) in manifest.path_deps.iter() {
+        if libs.contains_key(name) {
+            continue;
+        }
+        let lib = Manifest::read(path)?;
+        libs.insert(name.clone(), lib.clone());
+        collect_libs(&lib, libs)?;
+    }
+    Ok(())
+}
+
+fn dependency_renames(manifest: &Manifest) -> Renames {
+    manifest
+        .path_deps
+        .iter()
+        .map(|(name, _)| (name.clone(), format!("crate::{}", name)))
+        .collect()
+}
+
+impl Bundle {
+    fn expand(&mut self, file: &Path, renames: &Renames) -> io::Result<()> {
+        let text = fs::read_to_string(file)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file.display(), e)))?;
+        self.files += 1;
+        let children = child_dir(file);
+        let mut lines = text.lines();
+        // attributes of the next item, each possibly spread over lines
+        let mut attributes: Vec<Vec<&str>> = Vec::new();
+        while let Some(line) = lines.next() {
+            if line.trim_start().starts_with("#[") {
+                match attribute_end(line) {
+                    // the attribute and its item on the same line
+                    Some(end) if !code_part(&line[end..]).trim().is_empty() => {
+                        if is_test_cfg(&line[..end]) && module_decl(&line[end..]).is_some() {
+                            attributes.clear();
+                            continue;
+                        }
+                    }
+                    _ => {
+                        let mut attribute = vec![line];
+                        while !is_closed(&attribute) {
+                            match lines.next() {
+                                Some(next) => attribute.push(next),
+                                None => break,
+                            }
+                        }
+                        attributes.push(attribute);
+                        continue;
+                    }
+                }
+            }
+            let decl = module_decl(line);
+            if decl.is_some() && attributes.iter().any(|a| is_test_cfg(&a.join("\n"))) {
+                attributes.clear();
+                continue;
+            }
+            for attribute in attributes.drain(..) {
+                for attribute_line in attribute {
+                    self.source.push_str(&rename_paths(attribute_line, renames));
+                    self.source.push('\n');
+                }
+            }
+            match decl {
+                Some((head, name)) => {
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    self.source.push_str(&format!("{} {{\n", head));
+                    self.expand(&module_file(&children, name)?, renames)?;
+                    self.source.push_str(&format!("{}}}\n", indent));
+                }
+                None => {
+                    self.source.push_str(&rename_paths(line, renames));
+                    self.source.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn lines(&self) -> usize {
+        self.source.lines().count()
+    }
+
+    pub fn size(&self) -> usize {
+        self.source.chars().count()
+    }
+}
+
+/// Splits `pub mod name;` into `pub mod name` and `name`
+pub fn module_decl(line: &str) -> Option<(&str, &str)> {
+    let head = line.trim_end().strip_suffix(';')?;
+    let decl = head.trim_start();
+    let decl = ["pub(crate) ", "pub(super) ", "pub "]
+        .iter()
+        .find_map(|vis| decl.strip_prefix(vis))
+        .unwrap_or(decl);
+    let name = decl.strip_prefix("mod ")?.trim();
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    valid.then_some((head, name))
+}
+
+/// Whether the attribute compiles its item for the tests only, that is
+/// `#[cfg(test)]` or `#[cfg(all(test, ..))]` whatever the spacing, line
+/// breaks and comments
+pub fn is_test_cfg(attribute: &str) -> bool {
+    let code = attribute
+        .lines()
+        .map(code_part)
+        .flat_map(|l| l.chars())
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let Some(cfg) = code
+        .strip_prefix("#[cfg(")
+        .and_then(|c| c.strip_suffix(")]"))
+    else {
+        return false;
+    };
+    cfg == "test"
+        || cfg
+            .strip_prefix("all(")
+            .and_then(|c| c.strip_suffix(')'))
+            .is_some_and(|args| top_level_args(args).contains(&"test"))
+}
+
+/// Whether the brackets of an attribute started on the first line are all
+/// closed
+fn is_closed(lines: &[&str]) -> bool {
+    let mut depth = 0;
+    for c in lines.iter().flat_map(|l| code_part(l).chars()) {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+/// End of an attribute opened and closed on the line
+fn attribute_end(line: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in code_part(line).char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 1 => return Some(i + 1),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The line without its trailing `//` comment
+fn code_part(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == '/' && previous == '/' {
+            return &line[..i - 1];
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Arguments separated by the commas outside of parentheses
+fn top_level_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&args[start..]);
+    parts
+}
+
+/// Directory of the submodules declared in `file`
+fn child_dir(file: &Path) -> PathBuf {
+    let parent = file.parent().unwrap_or(Path::new("."));
+    match file.file_stem().and_then(|s| s.to_str()) {
+        Some("main" | "lib" | "mod") | None => parent.to_path_buf(),
+        Some(stem) => parent.join(stem),
+    }
+}
+
+fn module_file(dir: &Path, name: &str) -> io::Result<PathBuf> {
+    let candidates = [
+        dir.join(format!("{}.rs", name)),
+        dir.join(name).join("mod.rs"),
+    ];
+    candidates
+        .iter()
+        .find(|p| p.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("module {} not found in {}", name, dir.display()),
+            )
+        })
+}
+
+/// Rewrites the paths starting with one of the `renames` prefixes, leaving
+/// string literals and comments alone. Only the first path of a `use` line
+/// is rewritten, the ones nested in its braces being relative to it.
+pub fn rename_paths(line: &str, renames: &Renames) -> String {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(line.len());
+    let is_use = ["use ", "pub use ", "pub(crate) use "]
+        .iter()
+        .any(|u| line.trim_start().starts_with(u));
+    let mut paths_left = if is_use { 1 } else { usize::MAX };
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            out.extend(&chars[i..]);
+            break;
+        } else if c == '\'' {
+            // char literals, lifetimes are copied as they are
+            let end = match (chars.get(i + 1), chars.get(i + 2)) {
+                (Some('\\'), _) => (i + 3..chars.len())
+                    .find(|&j| chars[j] == '\'')
+                    .unwrap_or(i),
+                (Some(_), Some('\'')) => i + 2,
+                _ => i,
+            };
+            out.extend(&chars[i..=end]);
+            i = end + 1;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let ident = chars[start..i].iter().collect::<String>();
+            let path_start = start == 0 || !matches!(chars[start - 1], ':' | '$');
+            let followed = chars.get(i) == Some(&':') && chars.get(i + 1) == Some(&':');
+            if is_use && ident == "use" {
+                out.push_str(&ident);
+                continue;
+            }
+            let rename = renames.iter().find(|(from, _)| *from == ident);
+            match rename {
+                Some((_, to)) if path_start && followed && paths_left > 0 => out.push_str(to),
+                _ => out.push_str(&ident),
+            }
+            if path_start && followed {
+                paths_left = paths_left.saturating_sub(1);
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Type checks the bundle with rustc, `Err` holds the compiler output
+pub fn check(bundle: &Bundle) -> io::Result<Result<(), String>> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = env::temp_dir().join(format!(
+        "bundler-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir)?;
+    let file = dir.join("bundle.rs");
+    fs::write(&file, &bundle.source)?;
+    let output = Command::new("rustc")
+        .args(["--edition", &bundle.edition])
+        .args(["--crate-type", "bin", "--crate-name", "bundle"])
+        .args(["--emit=metadata", "-A", "warnings", "--out-dir"])
+        .arg(&dir)
+        .arg(&file)
+        .output();
+    let _ = fs::remove_dir_all(&dir);
+    let output = output?;
+    Ok(if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    })
+}
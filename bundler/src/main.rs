@@ -0,0 +1,96 @@
+//! Flattens a challenge crate and its path dependencies into the single
+//! source file CodinGame accepts, and checks that the result compiles.
+
+mod bundle;
+mod manifest;
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+#[derive(Debug, Clone)]
+struct Options {
+    dir: PathBuf,
+    /// Stdout when not given
+    output: Option<PathBuf>,
+    check: bool,
+}
+
+impl Options {
+    const USAGE: &'static str = "usage: bundler <crate_dir> [-o <file>] [--no-check]";
+
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let [dir, rest @ ..] = args else {
+            return Err(Self::USAGE.to_string());
+        };
+        let mut options = Self {
+            dir: PathBuf::from(dir),
+            output: None,
+            check: true,
+        };
+        let mut rest = rest.iter();
+        while let Some(flag) = rest.next() {
+            match flag.as_str() {
+                "--no-check" => options.check = false,
+                "-o" => {
+                    let path = rest.next().ok_or("missing value for -o")?;
+                    options.output = Some(PathBuf::from(path));
+                }
+                _ => return Err(format!("unknown option {}\n{}", flag, Self::USAGE)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let bundle = match bundle::bundle(&options.dir) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("failed to bundle {}: {}", options.dir.display(), e);
+            process::exit(1);
+        }
+    };
+    match options.output.as_ref() {
+        Some(path) => {
+            if let Err(e) = fs::write(path, &bundle.source) {
+                eprintln!("failed to write {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        }
+        None => print!("{}", bundle.source),
+    }
+    eprintln!(
+        "bundled {} files | {} lines | {} characters",
+        bundle.files,
+        bundle.lines(),
+        bundle.size()
+    );
+    if bundle.size() > bundle::SIZE_LIMIT {
+        eprintln!("warning: over the {} characters limit", bundle::SIZE_LIMIT);
+    }
+    if options.check {
+        match bundle::check(&bundle) {
+            Ok(Ok(())) => eprintln!("bundle compiles"),
+            Ok(Err(errors)) => {
+                eprintln!("{}bundle does not compile", errors);
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("failed to run rustc: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The few fields of a `Cargo.toml` the bundler needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// Crate name as used in paths, dashes replaced
+    pub name: String,
+    pub edition: String,
+    pub dir: PathBuf,
+    /// Path dependencies, by crate name
+    pub path_deps: Vec<(String, PathBuf)>,
+    /// Dependencies from a registry, which cannot be bundled
+    pub other_deps: Vec<String>,
+}
+
+impl Manifest {
+    pub fn read(dir: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(dir.join("Cargo.toml"))?;
+        Self::parse(&text, dir).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", dir.display(), e),
+            )
+        })
+    }
+
+    /// Line based, only understands `key = value` pairs and inline tables
+    pub fn parse(text: &str, dir: &Path) -> Result<Self, String> {
+        let mut manifest = Self {
+            name: String::new(),
+            edition: "2015".to_string(),
+            dir: dir.to_path_buf(),
+            path_deps: Vec::new(),
+            other_deps: Vec::new(),
+        };
+        let mut section = "";
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.starts_with('[') {
+                section = line;
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match section {
+                "[package]" if key == "name" => manifest.name = unquote(value).replace('-', "_"),
+                "[package]" if key == "edition" => manifest.edition = unquote(value).to_string(),
+                "[dependencies]" => {
+                    let name = key.replace('-', "_");
+                    match table_value(value, "path") {
+                        Some(path) => manifest.path_deps.push((name, dir.join(path))),
+                        None => manifest.other_deps.push(name),
+                    }
+                }
+                _ => (),
+            }
+        }
+        if manifest.name.is_empty() {
+            return Err("missing package name".to_string());
+        }
+        Ok(manifest)
+    }
+
+    /// `src/main.rs` of a binary, `src/lib.rs` of a library
+    pub fn root(&self, library: bool) -> PathBuf {
+        self.dir
+            .join("src")
+            .join(if library { "lib.rs" } else { "main.rs" })
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches('"')
+}
+
+/// Value of `key` in an inline table such as `{ path = "../common" }`
+fn table_value<'a>(table: &'a str, key: &str) -> Option<&'a str> {
+    let inner = table.strip_prefix('{')?.strip_suffix('}')?;
+    inner.split(',').find_map(|pair| {
+        let (k, v) = pair.split_once('=')?;
+        (k.trim() == key).then(|| unquote(v.trim()))
+    })
+}
@@ -0,0 +1,217 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{self, is_test_cfg, module_decl, rename_paths};
+use crate::manifest::Manifest;
+use crate::*;
+
+/// Writes `files` under a fresh temporary directory
+fn fixture(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = env::temp_dir().join(format!("bundler-fixture-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    root
+}
+
+fn renames(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect()
+}
+
+#[test]
+fn options_parse() {
+    let args = ["../fall_challenge_2022", "-o", "out.rs"].map(String::from);
+    let options = Options::parse(&args).unwrap();
+    assert_eq!(options.output, Some(PathBuf::from("out.rs")));
+    assert!(options.check);
+    assert!(Options::parse(&[]).is_err());
+    assert!(Options::parse(&args[..2]).is_err());
+    let args = ["x", "--no-check"].map(String::from);
+    assert!(!Options::parse(&args).unwrap().check);
+}
+
+#[test]
+fn manifest_dependencies() {
+    let text = r#"
+[package]
+name = "my-bot"
+edition = "2021" # comment
+
+[dependencies]
+cg-common = { path = "../cg_common" }
+rand = "0.8"
+
+[profile.release]
+debug = 1
+"#;
+    let manifest = Manifest::parse(text, Path::new("bots/my_bot")).unwrap();
+    assert_eq!(manifest.name, "my_bot");
+    assert_eq!(manifest.edition, "2021");
+    assert_eq!(
+        manifest.path_deps,
+        [(
+            "cg_common".to_string(),
+            PathBuf::from("bots/my_bot/../cg_common")
+        )]
+    );
+    assert_eq!(manifest.other_deps, ["rand"]);
+    assert!(Manifest::parse("[dependencies]\n", Path::new(".")).is_err());
+}
+
+#[test]
+fn module_declarations() {
+    assert_eq!(module_decl("mod game;"), Some(("mod game", "game")));
+    assert_eq!(
+        module_decl("    pub(crate) mod grid;  "),
+        Some(("    pub(crate) mod grid", "grid"))
+    );
+    assert_eq!(module_decl("mod inline {"), None);
+    assert_eq!(module_decl("let model = x;"), None);
+    assert_eq!(module_decl("// mod old;"), None);
+}
+
+#[test]
+fn test_attributes() {
+    for attribute in [
+        "#[cfg(test)]",
+        "    #[cfg(test)] // only the tests play turns",
+        "#[cfg( all(test, feature = \"slow\") )]",
+        "#[cfg(all(\n    unix, // comment\n    test,\n))]",
+    ] {
+        assert!(is_test_cfg(attribute), "{}", attribute);
+    }
+    for attribute in [
+        "#[cfg(any(test, unix))]",
+        "#[cfg(not(test))]",
+        "#[cfg(all(unix, not(test)))]",
+        "#[allow(dead_code)] // #[cfg(test)]",
+        "#[doc = \"// #[cfg(test)]\"]",
+    ] {
+        assert!(!is_test_cfg(attribute), "{}", attribute);
+    }
+}
+
+#[test]
+fn bundle_drops_test_modules() {
+    let main = "\
+mod game;
+#[cfg(test)] // only the tests play turns
+mod simulator;
+#[cfg(all(
+    test,
+    unix
+))]
+mod fixtures;
+#[cfg(test)] mod tests;
+#[allow(dead_code)] // kept
+mod rules;
+
+fn main() {}
+";
+    let root = fixture(
+        "test-modules",
+        &[
+            (
+                "bot/Cargo.toml",
+                "[package]\nname = \"bot\"\nedition = \"2021\"\n",
+            ),
+            ("bot/src/main.rs", main),
+            ("bot/src/game.rs", "pub fn score() {}\n"),
+            ("bot/src/rules.rs", "const BONUS: i32 = 1;\n"),
+            ("bot/src/simulator.rs", "this is not rust"),
+            ("bot/src/fixtures.rs", "this is not rust"),
+            ("bot/src/tests.rs", "this is not rust"),
+        ],
+    );
+    let bundle = bundle::bundle(&root.join("bot")).unwrap();
+    assert_eq!(bundle.files, 3);
+    assert!(!bundle.source.contains("not rust"));
+    assert!(!bundle.source.contains("cfg"));
+    assert!(bundle
+        .source
+        .contains("#[allow(dead_code)] // kept\nmod rules {\n"));
+    assert_eq!(bundle::check(&bundle).unwrap(), Ok(()));
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn paths_renamed_outside_literals() {
+    let lib = renames(&[("crate", "crate::common"), ("other", "crate::other")]);
+    assert_eq!(
+        rename_paths("use crate::grid::{Grid, other::X};", &lib),
+        "use crate::common::grid::{Grid, other::X};"
+    );
+    assert_eq!(
+        rename_paths("let s = \"crate::x\"; // crate::y", &lib),
+        "let s = \"crate::x\"; // crate::y"
+    );
+    assert_eq!(
+        rename_paths("other::f('\"', $crate::g(), crate_x::h)", &lib),
+        "crate::other::f('\"', $crate::g(), crate_x::h)"
+    );
+    assert_eq!(
+        rename_paths("fn f<'a>(x: &'a str) {}", &lib),
+        "fn f<'a>(x: &'a str) {}"
+    );
+}
+
+#[test]
+fn bundle_modules_and_dependency() {
+    let root = fixture(
+        "deps",
+        &[
+            ("bot/Cargo.toml", "[package]\nname = \"bot\"\nedition = \"2021\"\n\n[dependencies]\ncommon = { path = \"../common\" }\n"),
+            ("bot/src/main.rs", "mod game;\n#[cfg(test)]\nmod tests;\n\nfn main() {\n    println!(\"{}\", game::score());\n}\n"),
+            ("bot/src/game.rs", "pub mod rules;\n\npub fn score() -> i32 {\n    rules::BONUS + common::double(2)\n}\n"),
+            ("bot/src/game/rules.rs", "pub const BONUS: i32 = 1;\n"),
+            ("bot/src/tests.rs", "this is not rust"),
+            ("common/Cargo.toml", "[package]\nname = \"common\"\nedition = \"2021\"\n"),
+            ("common/src/lib.rs", "//! Shared code\nmod math;\npub use math::double;\n\npub fn unused() {}\n"),
+            ("common/src/math/mod.rs", "pub fn double(x: i32) -> i32 {\n    crate::unused();\n    x * 2\n}\n"),
+        ],
+    );
+    let bundle = bundle::bundle(&root.join("bot")).unwrap();
+    assert_eq!(bundle.files, 5);
+    assert!(!bundle.source.contains("tests"));
+    assert!(bundle.source.contains("mod game {\npub mod rules {\n"));
+    assert!(bundle.source.contains("crate::common::double(2)"));
+    assert!(bundle.source.contains("crate::common::unused();"));
+    assert_eq!(bundle::check(&bundle).unwrap(), Ok(()));
+    // a broken module is reported by the check
+    fs::write(
+        root.join("bot/src/game/rules.rs"),
+        "pub const BONUS: i32 = \"1\";\n",
+    )
+    .unwrap();
+    let bundle = bundle::bundle(&root.join("bot")).unwrap();
+    assert!(bundle::check(&bundle)
+        .unwrap()
+        .unwrap_err()
+        .contains("mismatched types"));
+    // as is a missing one
+    fs::remove_file(root.join("bot/src/game/rules.rs")).unwrap();
+    assert!(bundle::bundle(&root.join("bot")).is_err());
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn challenge_crates_compile() {
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    for name in [
+        "spring_challenge_2021",
+        "spring_challenge_2022",
+        "fall_challenge_2022",
+    ] {
+        let bundle = bundle::bundle(&repo.join(name)).unwrap();
+        assert!(bundle.files > 1, "{}", name);
+        assert!(bundle.size() < bundle::SIZE_LIMIT, "{}", name);
+        assert_eq!(bundle::check(&bundle).unwrap(), Ok(()), "{}", name);
+    }
+}
@@ -0,0 +1,180 @@
+//! Line based access to the referee that records the whole conversation, so
+//! a match can be replayed offline.
+//!
+//! Recorded lines are prefixed with `< ` for input and `> ` for our
+//! commands, anything else in a replay file is ignored. The recording goes
+//! to stderr by default, so the debug log of an online match can be saved
+//! and replayed as is.
+
+use std::any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Cursor, Write};
+use std::str::FromStr;
+
+use crate::input::{ErrorKind, FromLine, ParseError, Words};
+
+pub struct Console {
+    source: Box<dyn BufRead>,
+    record: Option<Box<dyn Write>>,
+    /// Commands of the replayed match, one entry per turn
+    expected: VecDeque<Vec<String>>,
+    replaying: bool,
+    turn: u32,
+    /// Input lines read so far
+    lines: usize,
+}
+
+impl Console {
+    /// Reads `source` and records to stderr
+    pub fn new(source: Box<dyn BufRead>) -> Self {
+        Self {
+            source,
+            record: Some(Box::new(io::stderr())),
+            expected: VecDeque::new(),
+            replaying: false,
+            turn: 0,
+            lines: 0,
+        }
+    }
+
+    /// Reads stdin and records to stderr
+    pub fn stdin() -> Self {
+        Self::new(Box::new(BufReader::new(io::stdin())))
+    }
+
+    /// Reads stdin and records to a file
+    pub fn recording(path: &str) -> io::Result<Self> {
+        Ok(Self {
+            record: Some(Box::new(BufWriter::new(File::create(path)?))),
+            ..Self::stdin()
+        })
+    }
+
+    /// Reads the input of a recorded match instead of stdin
+    pub fn replay(recording: &str) -> Self {
+        let mut input = String::new();
+        let mut expected = VecDeque::new();
+        let mut in_output = false;
+        for line in recording.lines() {
+            if let Some(l) = line.strip_prefix('<') {
+                input.push_str(l.strip_prefix(' ').unwrap_or(l));
+                input.push('\n');
+                in_output = false;
+            } else if let Some(l) = line.strip_prefix("> ") {
+                if !in_output {
+                    expected.push_back(Vec::new());
+                    in_output = true;
+                }
+                expected.back_mut().unwrap().push(l.to_string());
+            }
+        }
+        Self {
+            source: Box::new(Cursor::new(input.into_bytes())),
+            record: None,
+            expected,
+            replaying: true,
+            turn: 0,
+            lines: 0,
+        }
+    }
+
+    /// Picks the console from the command line: `replay <file>` or
+    /// `record <file>`, stdin recorded to stderr otherwise
+    pub fn from_args(args: &[String]) -> io::Result<Self> {
+        match args {
+            [mode, path, ..] if mode == "replay" => {
+                Ok(Self::replay(&std::fs::read_to_string(path)?))
+            }
+            [mode, path, ..] if mode == "record" => Self::recording(path),
+            _ => Ok(Self::stdin()),
+        }
+    }
+
+    /// Appends the next line to `buf` like `Stdin::read_line`, 0 at the end
+    /// of the input
+    pub fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let start = buf.len();
+        let n = self.source.read_line(buf)?;
+        if n > 0 {
+            self.lines += 1;
+            if let Some(record) = self.record.as_mut() {
+                let _ = writeln!(record, "< {}", buf[start..].trim_end());
+            }
+        }
+        Ok(n)
+    }
+
+    /// Reads the next line split on whitespace, `None` at the end of the
+    /// input
+    pub fn next_words(&mut self) -> Result<Option<Words>, ParseError> {
+        let mut line = String::new();
+        match self.read_line(&mut line) {
+            Ok(n) => Ok((n > 0).then(|| Words::new(self.lines, &line))),
+            Err(e) => Err(self.error("line", ErrorKind::Io(e.to_string()))),
+        }
+    }
+
+    /// Reads the next line split on whitespace, `name` tells what was
+    /// expected when the input has ended
+    pub fn words(&mut self, name: &'static str) -> Result<Words, ParseError> {
+        match self.next_words() {
+            Ok(Some(words)) => Ok(words),
+            Ok(None) => Err(self.error(name, ErrorKind::EndOfInput)),
+            Err(e) => Err(ParseError { field: name, ..e }),
+        }
+    }
+
+    /// Error on the line that could not be read
+    fn error(&self, field: &'static str, kind: ErrorKind) -> ParseError {
+        ParseError {
+            field,
+            line_number: self.lines + 1,
+            line: String::new(),
+            kind,
+        }
+    }
+
+    /// Reads a line holding a single value
+    pub fn parse<T: FromStr>(&mut self, name: &'static str) -> Result<T, ParseError>
+    where
+        T::Err: fmt::Display,
+    {
+        self.words(name)?.field(name)
+    }
+
+    /// Reads a line holding a whole record
+    pub fn read<T: FromLine>(&mut self) -> Result<T, ParseError> {
+        T::from_line(&mut self.words(any::type_name::<T>())?)
+    }
+
+    /// Sends the commands of one turn, when replaying they are also compared
+    /// with the recorded ones and `false` is returned on a mismatch
+    pub fn write_turn(&mut self, commands: &str) -> bool {
+        self.turn += 1;
+        if let Some(record) = self.record.as_mut() {
+            for line in commands.lines() {
+                let _ = writeln!(record, "> {}", line);
+            }
+            let _ = record.flush();
+        }
+        print!("{}", commands);
+        io::stdout().flush().unwrap();
+        if !self.replaying {
+            return true;
+        }
+        let expected = self.expected.pop_front().unwrap_or_default();
+        let matches = commands.lines().eq(expected.iter().map(String::as_str));
+        if !matches {
+            eprintln!("turn {} differs from the recording", self.turn);
+            for line in expected.iter() {
+                eprintln!("- {}", line);
+            }
+            for line in commands.lines() {
+                eprintln!("+ {}", line);
+            }
+        }
+        matches
+    }
+}
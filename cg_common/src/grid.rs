@@ -0,0 +1,134 @@
+use std::ops::{Index, IndexMut};
+
+use crate::vec2::Vec2i;
+
+/// Rectangular map stored row by row, addressed either by position or by
+/// the index of the cell in the input order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid2D<T> {
+    /// `cells` are row by row, panics when their count does not match
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid of {}x{}", width, height);
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Vec2i) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.width && (pos.y as usize) < self.height
+    }
+
+    pub fn index_of(&self, pos: Vec2i) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.y as usize * self.width + pos.x as usize)
+    }
+
+    pub fn pos_of(&self, index: usize) -> Vec2i {
+        Vec2i::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+
+    pub fn get(&self, pos: Vec2i) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2i) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Adjacent positions inside the grid, left, right, up then down
+    pub fn neighbours(&self, pos: Vec2i) -> impl Iterator<Item = Vec2i> + '_ {
+        pos.neighbours().into_iter().filter(|p| self.contains(*p))
+    }
+
+    /// Indices of the adjacent cells, in the order of `neighbours`
+    pub fn neighbour_indices(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbours(self.pos_of(index))
+            .map(|p| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// All positions, in the order of the cells
+    pub fn positions(&self) -> impl Iterator<Item = Vec2i> + '_ {
+        (0..self.cells.len()).map(|i| self.pos_of(i))
+    }
+
+    /// Grid of the same size with every cell mapped
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid2D<U> {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<usize> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.cells[index]
+    }
+}
+
+impl<T> IndexMut<usize> for Grid2D<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.cells[index]
+    }
+}
+
+impl<T> Index<Vec2i> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2i) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} outside of the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Vec2i> for Grid2D<T> {
+    fn index_mut(&mut self, pos: Vec2i) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} outside of the grid", pos))
+    }
+}
@@ -0,0 +1,145 @@
+//! Typed reading of the referee input. Values are separated by any amount
+//! of whitespace and every failure tells which field of which line is wrong.
+
+use std::error::Error;
+use std::fmt;
+use std::process;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The input ended before the line could be read
+    EndOfInput,
+    /// The line holds fewer values than expected
+    Missing,
+    /// The value does not parse, with the reason
+    Invalid(String),
+    /// The line could not be read, with the reason
+    Io(String),
+}
+
+/// Input that does not follow the protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub field: &'static str,
+    /// Counted from 1 over the whole input
+    pub line_number: usize,
+    pub line: String,
+    pub kind: ErrorKind,
+}
+
+impl ParseError {
+    /// Reports the error and ends the bot, the rest of the input cannot be
+    /// trusted anymore
+    pub fn exit(&self) -> ! {
+        eprintln!("{}", self);
+        process::exit(1)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line_number)?;
+        match &self.kind {
+            ErrorKind::EndOfInput => write!(f, "input ended while reading {}", self.field),
+            ErrorKind::Missing => write!(f, "missing {} in {:?}", self.field, self.line),
+            ErrorKind::Invalid(reason) => {
+                write!(f, "invalid {} in {:?}: {}", self.field, self.line, reason)
+            }
+            ErrorKind::Io(reason) => write!(f, "failed to read {}: {}", self.field, reason),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Record held by a single input line, e.g. an entity
+pub trait FromLine: Sized {
+    fn from_line(words: &mut Words) -> Result<Self, ParseError>;
+}
+
+/// Whitespace separated values of one input line, parsed in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Words {
+    line_number: usize,
+    line: String,
+    words: Vec<String>,
+    next: usize,
+}
+
+impl Words {
+    pub fn new(line_number: usize, line: &str) -> Self {
+        Self {
+            line_number,
+            line: line.trim_end().to_string(),
+            words: line.split_whitespace().map(String::from).collect(),
+            next: 0,
+        }
+    }
+
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Values not parsed yet
+    pub fn remaining(&self) -> usize {
+        self.words.len() - self.next
+    }
+
+    pub fn error(&self, field: &'static str, kind: ErrorKind) -> ParseError {
+        ParseError {
+            field,
+            line_number: self.line_number,
+            line: self.line.clone(),
+            kind,
+        }
+    }
+
+    /// Parses the next value
+    pub fn field<T: FromStr>(&mut self, name: &'static str) -> Result<T, ParseError>
+    where
+        T::Err: fmt::Display,
+    {
+        let word = self
+            .words
+            .get(self.next)
+            .ok_or_else(|| self.error(name, ErrorKind::Missing))?;
+        let value = word
+            .parse()
+            .map_err(|e| self.error(name, ErrorKind::Invalid(format!("{:?} {}", word, e))))?;
+        self.next += 1;
+        Ok(value)
+    }
+
+    /// Parses the next value as `0` or `1`
+    pub fn flag(&mut self, name: &'static str) -> Result<bool, ParseError> {
+        match self.field::<u8>(name)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            v => {
+                self.next -= 1;
+                Err(self.error(name, ErrorKind::Invalid(format!("{} is not 0 or 1", v))))
+            }
+        }
+    }
+
+    /// Parses all the values left
+    pub fn rest<T: FromStr>(&mut self, name: &'static str) -> Result<Vec<T>, ParseError>
+    where
+        T::Err: fmt::Display,
+    {
+        (0..self.remaining()).map(|_| self.field(name)).collect()
+    }
+}
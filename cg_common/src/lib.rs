@@ -0,0 +1,20 @@
+//! Building blocks shared by the challenge bots: vectors, grids with their
+//! breadth first searches, the console reading the referee input and the
+//! rules of the games that are played locally.
+//!
+//! Bots are bundled into a single file for submission, see the bundler.
+
+mod console;
+mod grid;
+mod input;
+mod search;
+pub mod spring2022;
+#[cfg(test)]
+mod tests;
+mod vec2;
+
+pub use console::Console;
+pub use grid::Grid2D;
+pub use input::{ErrorKind, FromLine, ParseError, Words};
+pub use search::{flood_fill, DistanceMap};
+pub use vec2::{Vec2, Vec2f, Vec2i};
@@ -0,0 +1,107 @@
+//! Breadth first searches over a `Grid2D` with 4-neighbour moves.
+//!
+//! Walls are given by a `passable` predicate, so the same grid can be
+//! searched with different rules, e.g. with or without the enemy units.
+
+use std::collections::VecDeque;
+
+use crate::grid::Grid2D;
+use crate::vec2::Vec2i;
+
+/// Number of steps from the nearest source to every cell, `None` when it
+/// cannot be reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMap {
+    distances: Grid2D<Option<u32>>,
+}
+
+impl DistanceMap {
+    /// Sources are at distance 0 even if they are not passable themselves,
+    /// like a unit standing on a tile that is about to disappear
+    pub fn new<T>(
+        grid: &Grid2D<T>,
+        sources: impl IntoIterator<Item = Vec2i>,
+        passable: impl Fn(Vec2i, &T) -> bool,
+    ) -> Self {
+        let mut distances = grid.map(|_| None);
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if let Some(d @ None) = distances.get_mut(source) {
+                *d = Some(0);
+                queue.push_back(source);
+            }
+        }
+        while let Some(pos) = queue.pop_front() {
+            let next = distances[pos].unwrap() + 1;
+            for n in grid.neighbours(pos) {
+                if distances[n].is_none() && passable(n, &grid[n]) {
+                    distances[n] = Some(next);
+                    queue.push_back(n);
+                }
+            }
+        }
+        Self { distances }
+    }
+
+    pub fn get(&self, pos: Vec2i) -> Option<u32> {
+        self.distances.get(pos).copied().flatten()
+    }
+
+    pub fn grid(&self) -> &Grid2D<Option<u32>> {
+        &self.distances
+    }
+
+    /// Reachable positions with their distance, in the order of the cells
+    pub fn reachable(&self) -> impl Iterator<Item = (Vec2i, u32)> + '_ {
+        self.distances
+            .positions()
+            .filter_map(|p| self.get(p).map(|d| (p, d)))
+    }
+
+    /// Adjacent position one step closer to the sources, the first one in
+    /// the order of `Vec2i::DIRECTIONS` on a tie
+    pub fn step(&self, pos: Vec2i) -> Option<Vec2i> {
+        let d = self.get(pos)?;
+        self.distances
+            .neighbours(pos)
+            .find(|n| d > 0 && self.get(*n) == Some(d - 1))
+    }
+
+    /// Positions from `from` to the nearest source, `from` excluded
+    pub fn path(&self, from: Vec2i) -> Option<Vec<Vec2i>> {
+        let mut path = Vec::with_capacity(self.get(from)? as usize);
+        let mut pos = from;
+        while let Some(next) = self.step(pos) {
+            path.push(next);
+            pos = next;
+        }
+        Some(path)
+    }
+}
+
+/// Passable positions connected to `start`, in the order they are reached
+pub fn flood_fill<T>(
+    grid: &Grid2D<T>,
+    start: Vec2i,
+    passable: impl Fn(Vec2i, &T) -> bool,
+) -> Vec<Vec2i> {
+    let mut seen = grid.map(|_| false);
+    let mut region = Vec::new();
+    if grid.get(start).is_none_or(|cell| !passable(start, cell)) {
+        return region;
+    }
+    seen[start] = true;
+    region.push(start);
+    let mut next = 0;
+    while next < region.len() {
+        let pos = region[next];
+        next += 1;
+        for n in grid.neighbours(pos) {
+            if !seen[n] && passable(n, &grid[n]) {
+                seen[n] = true;
+                region.push(n);
+            }
+        }
+    }
+    region
+}
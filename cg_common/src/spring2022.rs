@@ -0,0 +1,31 @@
+//! Rules of Spring Challenge 2022 (Spider Attack), shared by the bot and the
+//! local referee so the two can't drift apart.
+
+use crate::Vec2f;
+
+/// Bottom right corner of the map, where the second player's base is
+pub const MAP: Vec2f = Vec2f::new(17630.0, 9000.0);
+/// Turns of a whole match
+pub const MAX_TURNS: u32 = 220;
+
+pub const BASE_HP: i32 = 3;
+/// Monsters inside it are seen by the base's owner
+pub const BASE_VIEW_RANGE: f32 = 6000.0;
+/// Monsters this close to a base damage it
+pub const BASE_DAMAGE_RANGE: f32 = 300.0;
+
+pub const HERO_SPEED: f32 = 800.0;
+pub const HERO_VIEW_RANGE: f32 = 2200.0;
+pub const HERO_DAMAGE: i32 = 2;
+pub const ATTACK_RANGE: f32 = 800.0;
+
+pub const SPELL_COST: i32 = 10;
+pub const WIND_RANGE: f32 = 1280.0;
+pub const WIND_PUSH: f32 = 2200.0;
+pub const SHIELD_RANGE: f32 = 2200.0;
+pub const SHIELD_DURATION: i32 = 12;
+pub const CONTROL_RANGE: f32 = 2200.0;
+
+pub const MONSTER_SPEED: f32 = 400.0;
+/// Monsters this close to a base head straight for it
+pub const AGGRO_RANGE: f32 = 5000.0;
@@ -0,0 +1,234 @@
+use crate::*;
+
+/// Map from rows of `#` walls and `.` floors
+fn maze(rows: &[&str]) -> Grid2D<bool> {
+    let cells = rows
+        .iter()
+        .flat_map(|r| r.chars().map(|c| c == '.'))
+        .collect();
+    Grid2D::from_cells(rows[0].len(), rows.len(), cells)
+}
+
+fn floor(_: Vec2i, cell: &bool) -> bool {
+    *cell
+}
+
+#[test]
+fn vec2_arithmetic() {
+    let a = Vec2i::new(1, 2);
+    let b = Vec2i::new(4, -2);
+    assert_eq!(a + b, Vec2i::new(5, 0));
+    assert_eq!(a - b, Vec2i::new(-3, 4));
+    assert_eq!(-a * 3, Vec2i::new(-3, -6));
+    assert_eq!(a.dot(&b), 0);
+    assert_eq!(a.distance_squared(&b), 25);
+    assert_eq!(a.manhattan(&b), 7);
+    assert_eq!(a.perp(), Vec2i::new(-2, 1));
+    let mut c = a;
+    c += b;
+    c -= a;
+    assert_eq!(c, b);
+    assert_eq!(
+        a.neighbours(),
+        [(0, 2), (2, 2), (1, 1), (1, 3)].map(|(x, y)| Vec2i::new(x, y))
+    );
+    assert!(a.in_bounds(&Vec2i::new(1, 2)));
+    assert!(!b.in_bounds(&Vec2i::new(10, 10)));
+    assert_eq!(b.to_string(), "4 -2");
+}
+
+#[test]
+fn vec2_float() {
+    let v = Vec2f::new(3.0, 4.0);
+    assert_eq!(v.magnitude(), 5.0);
+    assert_eq!(v.distance(&Vec2f::ZERO), 5.0);
+    assert_eq!(v.normalize(), Vec2f::new(0.6, 0.8));
+    assert_eq!((v * 0.5).to_string(), "1 2");
+    assert_eq!(Vec2::<f64>::new(-0.7, 9.9).to_string(), "0 9");
+}
+
+#[test]
+fn grid_indexing() {
+    let mut grid = Grid2D::new(3, 2, 0);
+    assert_eq!((grid.width(), grid.height(), grid.len()), (3, 2, 6));
+    grid[Vec2i::new(2, 1)] = 5;
+    grid[1] = 1;
+    assert_eq!(grid[5], 5);
+    assert_eq!(grid.get(Vec2i::new(1, 0)), Some(&1));
+    assert_eq!(grid.get(Vec2i::new(3, 0)), None);
+    assert_eq!(grid.get(Vec2i::new(0, -1)), None);
+    assert_eq!(grid.index_of(Vec2i::new(0, 1)), Some(3));
+    assert_eq!(grid.pos_of(4), Vec2i::new(1, 1));
+    let positions = grid.positions().collect::<Vec<_>>();
+    assert_eq!(positions[4], Vec2i::new(1, 1));
+    assert_eq!(grid.map(|c| c * 2).iter().sum::<i32>(), 12);
+    // corners have two neighbours, edges three
+    assert_eq!(
+        grid.neighbours(Vec2i::ZERO).collect::<Vec<_>>(),
+        [Vec2i::new(1, 0), Vec2i::new(0, 1)]
+    );
+    assert_eq!(grid.neighbour_indices(1).collect::<Vec<_>>(), [0, 2, 4]);
+}
+
+#[test]
+#[should_panic(expected = "outside of the grid")]
+fn grid_out_of_bounds() {
+    let grid = Grid2D::new(2, 2, 0);
+    let _ = grid[Vec2i::new(2, 0)];
+}
+
+#[test]
+fn distance_map_around_walls() {
+    let grid = maze(&["....#", ".##.#", "...#."]);
+    let map = DistanceMap::new(&grid, [Vec2i::new(3, 1)], floor);
+    assert_eq!(map.get(Vec2i::new(3, 1)), Some(0));
+    assert_eq!(map.get(Vec2i::new(0, 0)), Some(4));
+    assert_eq!(map.get(Vec2i::new(2, 2)), Some(8));
+    // walls and the cell walled off are unreachable
+    assert_eq!(map.get(Vec2i::new(4, 0)), None);
+    assert_eq!(map.get(Vec2i::new(4, 2)), None);
+    assert_eq!(map.get(Vec2i::new(9, 9)), None);
+    assert_eq!(map.reachable().count(), 9);
+    assert_eq!(map.step(Vec2i::new(0, 1)), Some(Vec2i::new(0, 0)));
+    assert_eq!(map.step(Vec2i::new(3, 1)), None);
+    assert_eq!(
+        map.path(Vec2i::new(1, 2)).unwrap(),
+        [(0, 2), (0, 1), (0, 0), (1, 0), (2, 0), (3, 0), (3, 1)].map(|(x, y)| Vec2i::new(x, y))
+    );
+    assert_eq!(map.path(Vec2i::new(4, 2)), None);
+}
+
+#[test]
+fn distance_map_nearest_source() {
+    let grid = maze(&["......"]);
+    let sources = [Vec2i::new(0, 0), Vec2i::new(5, 0), Vec2i::new(0, 0)];
+    let map = DistanceMap::new(&grid, sources, floor);
+    let distances = map.grid().iter().map(|d| d.unwrap()).collect::<Vec<_>>();
+    assert_eq!(distances, [0, 1, 2, 2, 1, 0]);
+    // a source on a wall still spreads
+    let grid = maze(&["#.."]);
+    let map = DistanceMap::new(&grid, [Vec2i::ZERO], floor);
+    assert_eq!(map.get(Vec2i::new(2, 0)), Some(2));
+}
+
+#[test]
+fn flood_fill_regions() {
+    let grid = maze(&["..#..", "..#..", "###.."]);
+    let region = flood_fill(&grid, Vec2i::new(0, 0), floor);
+    assert_eq!(region.len(), 4);
+    assert_eq!(region[0], Vec2i::ZERO);
+    assert_eq!(flood_fill(&grid, Vec2i::new(4, 2), floor).len(), 6);
+    assert!(flood_fill(&grid, Vec2i::new(2, 0), floor).is_empty());
+    assert!(flood_fill(&grid, Vec2i::new(-1, 0), floor).is_empty());
+}
+
+/// Record of the tests, `x y kind`
+#[derive(Debug, PartialEq)]
+struct Unit {
+    pos: Vec2i,
+    flying: bool,
+}
+
+impl FromLine for Unit {
+    fn from_line(words: &mut Words) -> Result<Self, ParseError> {
+        Ok(Self {
+            pos: Vec2i::new(words.field("x")?, words.field("y")?),
+            flying: words.flag("flying")?,
+        })
+    }
+}
+
+#[test]
+fn words_parsing() {
+    let mut words = Words::new(7, "3  -1 0.5\tabc\r\n");
+    assert_eq!(words.len(), 4);
+    assert_eq!(words.line(), "3  -1 0.5\tabc");
+    assert_eq!(words.line_number(), 7);
+    assert_eq!(words.field::<u32>("a"), Ok(3));
+    assert_eq!(words.field::<i32>("b"), Ok(-1));
+    assert_eq!(words.field::<f32>("c"), Ok(0.5));
+    assert_eq!(words.remaining(), 1);
+    assert_eq!(words.rest::<String>("d"), Ok(vec!["abc".to_string()]));
+    assert!(Words::new(1, "\n").is_empty());
+}
+
+#[test]
+fn words_errors() {
+    let mut words = Words::new(3, "1 x 2");
+    words.field::<i32>("id").unwrap();
+    let error = words.field::<i32>("health").unwrap_err();
+    assert_eq!(error.field, "health");
+    assert_eq!(error.line_number, 3);
+    assert_eq!(error.line, "1 x 2");
+    assert_eq!(
+        error.to_string(),
+        "line 3: invalid health in \"1 x 2\": \"x\" invalid digit found in string"
+    );
+    // nothing is consumed on failure
+    assert_eq!(words.field::<String>("health"), Ok("x".to_string()));
+    assert_eq!(
+        words.flag("shield").unwrap_err().kind,
+        ErrorKind::Invalid("2 is not 0 or 1".to_string())
+    );
+    words.field::<u8>("shield").unwrap();
+    let error = words.field::<u8>("vx").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Missing);
+    assert_eq!(error.to_string(), "line 3: missing vx in \"1 x 2\"");
+}
+
+#[test]
+fn console_replay() {
+    let recording = "\
+debug output is ignored
+< 1 2
+<
+> MOVE 1 2
+> WAIT
+< 3
+> WAIT
+";
+    let mut console = Console::replay(recording);
+    let mut buf = String::new();
+    assert_eq!(console.read_line(&mut buf).unwrap(), 4);
+    assert_eq!(console.read_line(&mut buf).unwrap(), 1);
+    assert_eq!(buf, "1 2\n\n");
+    assert!(console.write_turn("MOVE 1 2\nWAIT\n"));
+    assert_eq!(console.parse::<u32>("count"), Ok(3));
+    assert!(!console.write_turn("MOVE 0 0\n"));
+    assert_eq!(console.read_line(&mut buf).unwrap(), 0);
+    assert_eq!(console.next_words(), Ok(None));
+    let error = console.parse::<u32>("count").unwrap_err();
+    assert_eq!(error.to_string(), "line 4: input ended while reading count");
+}
+
+#[test]
+fn console_read_error() {
+    let input = std::io::Cursor::new(b"1 2\n\xff\n".to_vec());
+    let mut console = Console::new(Box::new(input));
+    assert_eq!(console.parse::<String>("a"), Ok("1".to_string()));
+    let error = console.parse::<u32>("count").unwrap_err();
+    assert_eq!((error.field, error.line_number), ("count", 2));
+    assert!(matches!(error.kind, ErrorKind::Io(_)), "{}", error);
+    assert!(error
+        .to_string()
+        .starts_with("line 2: failed to read count: "));
+}
+
+#[test]
+fn console_records() {
+    let mut console = Console::replay("< 4 5 1\n<  0\t6   0 \r\n< 1 1\n");
+    assert_eq!(
+        console.read::<Unit>(),
+        Ok(Unit {
+            pos: Vec2i::new(4, 5),
+            flying: true
+        })
+    );
+    assert!(!console.read::<Unit>().unwrap().flying);
+    let error = console.read::<Unit>().unwrap_err();
+    assert_eq!((error.field, error.line_number), ("flying", 3));
+    assert_eq!(
+        console.read::<Unit>().unwrap_err().kind,
+        ErrorKind::EndOfInput
+    );
+}
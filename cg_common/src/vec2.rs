@@ -0,0 +1,174 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Point or direction on a 2D map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// Grid coordinates
+pub type Vec2i = Vec2<i32>;
+/// Continuous map coordinates
+pub type Vec2f = Vec2<f32>;
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Vec2<T> {
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (*other - *self).length_squared()
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> Vec2<T> {
+    /// Rotated by a quarter turn
+    pub fn perp(&self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+}
+
+impl<T: Copy + PartialOrd + Default> Vec2<T> {
+    /// Inside the rectangle from the origin to `bounds`, both included
+    pub fn in_bounds(&self, bounds: &Self) -> bool {
+        let zero = T::default();
+        self.x >= zero && self.x <= bounds.x && self.y >= zero && self.y <= bounds.y
+    }
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {$(
+        impl Vec2<$t> {
+            pub const ZERO: Self = Self::new(0, 0);
+            /// Left, right, up and down, y pointing down as on the screen
+            pub const DIRECTIONS: [Self; 4] = [
+                Self::new(-1, 0),
+                Self::new(1, 0),
+                Self::new(0, -1),
+                Self::new(0, 1),
+            ];
+
+            pub fn manhattan(&self, other: &Self) -> $t {
+                (other.x - self.x).abs() + (other.y - self.y).abs()
+            }
+
+            /// The four adjacent points, in the order of `DIRECTIONS`
+            pub fn neighbours(&self) -> [Self; 4] {
+                Self::DIRECTIONS.map(|d| *self + d)
+            }
+        }
+
+        impl fmt::Display for Vec2<$t> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{} {}", self.x, self.y)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {$(
+        impl Vec2<$t> {
+            pub const ZERO: Self = Self::new(0.0, 0.0);
+
+            pub fn distance(&self, other: &Self) -> $t {
+                let dx = other.x - self.x;
+                let dy = other.y - self.y;
+                (dx * dx + dy * dy).sqrt()
+            }
+
+            pub fn magnitude(&self) -> $t {
+                self.distance(&Self::ZERO)
+            }
+
+            pub fn normalize(&self) -> Self {
+                let m = self.magnitude();
+                Self {
+                    x: self.x / m,
+                    y: self.y / m,
+                }
+            }
+        }
+
+        /// Truncated to integers, which is what the referees read
+        impl fmt::Display for Vec2<$t> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{} {}", self.x as i64, self.y as i64)
+            }
+        }
+    )*};
+}
+
+impl_int!(i32, i64);
+impl_float!(f32, f64);
+
+impl<T: Add<Output = T>> Add for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vec2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Vec2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Vec2<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Vec2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
@@ -0,0 +1,96 @@
+//! Per turn split of our units between the tiles we want to take.
+//!
+//! Every tile we don't own asks for one unit, plus one for each enemy unit
+//! on or next to it since fights remove units one for one. Stacks next to
+//! enemy units keep enough of them home to hold their tile. The closest
+//! stack and target pairs are served first, what is left walks to the
+//! nearest tile we don't own.
+
+use std::collections::BTreeMap;
+
+use cg_common::{DistanceMap, Vec2i};
+
+use crate::{check_move, Action, Distances, Grid, FOE};
+
+/// Units of the opponent on the tile and next to it
+fn threat(grid: &Grid, pos: Vec2i) -> u32 {
+    std::iter::once(pos)
+        .chain(grid.cells.neighbours(pos))
+        .map(|p| &grid.cells[p])
+        .filter(|cell| cell.owner == FOE)
+        .map(|cell| cell.units)
+        .sum()
+}
+
+/// Tile next to `from` on the shortest way to `to`, `map` being the
+/// distances from `from`
+fn first_step(map: &DistanceMap, from: Vec2i, to: Vec2i) -> Option<Vec2i> {
+    let mut path = map.path(to)?;
+    path.pop();
+    Some(path.pop().unwrap_or(to)).filter(|&step| step != from)
+}
+
+/// Moves of the turn, stacks split between several targets
+pub fn allocate_moves(grid: &Grid, distances: &Distances) -> Vec<Action> {
+    let stacks = grid
+        .mine
+        .iter()
+        .map(|&i| grid.cells.pos_of(i))
+        .filter(|&pos| grid.cells[pos].units > 0)
+        .collect::<Vec<_>>();
+    let mut free = stacks
+        .iter()
+        .map(|&pos| {
+            let units = grid.cells[pos].units;
+            units - threat(grid, pos).min(units)
+        })
+        .collect::<Vec<_>>();
+    let mut demand = grid.cells.map(|_| 0);
+    for &pos in grid.others.iter() {
+        if grid.cells[pos].walkable() {
+            demand[pos] = 1 + threat(grid, pos);
+        }
+    }
+
+    // closest pairs first
+    let maps = stacks
+        .iter()
+        .map(|&pos| grid.distances([pos]))
+        .collect::<Vec<_>>();
+    let mut pairs = Vec::new();
+    for (s, map) in maps.iter().enumerate() {
+        for (pos, d) in map.reachable() {
+            if demand[pos] > 0 {
+                pairs.push((d, s, pos));
+            }
+        }
+    }
+    pairs.sort();
+
+    // stacks heading the same way leave together
+    let mut moves = BTreeMap::new();
+    for (_, s, target) in pairs {
+        let amount = free[s].min(demand[target]);
+        if amount == 0 {
+            continue;
+        }
+        let Some(step) = first_step(&maps[s], stacks[s], target) else {
+            continue;
+        };
+        free[s] -= amount;
+        demand[target] -= amount;
+        *moves.entry((stacks[s], step)).or_insert(0) += amount;
+    }
+    for (s, &from) in stacks.iter().enumerate() {
+        if free[s] == 0 {
+            continue;
+        }
+        if let Some(Action::Move(amount, from, step)) = check_move(distances, from, free[s]) {
+            *moves.entry((from, step)).or_insert(0) += amount;
+        }
+    }
+    moves
+        .into_iter()
+        .map(|((from, step), amount)| Action::Move(amount, from, step))
+        .collect()
+}
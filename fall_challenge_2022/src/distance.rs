@@ -0,0 +1,32 @@
+//! Walking distances over the grid. Units can't stand on grass or on a
+//! recycler, so both block the way.
+
+use cg_common::{DistanceMap, Vec2i};
+
+use crate::{Cell, Grid};
+
+impl Cell {
+    pub fn walkable(&self) -> bool {
+        self.scrap_amount > 0 && !self.recycler
+    }
+}
+
+impl Grid {
+    /// Steps from the nearest of `sources` to every tile a unit can reach
+    pub fn distances(&self, sources: impl IntoIterator<Item = Vec2i>) -> DistanceMap {
+        DistanceMap::new(&self.cells, sources, |_, cell| cell.walkable())
+    }
+
+    /// First step from `from` towards `to`, aiming for the reachable tile
+    /// closest to `to` when the target itself can't be walked to
+    pub fn step(&self, from: Vec2i, to: Vec2i) -> Option<Vec2i> {
+        let (goal, _) = self
+            .distances([from])
+            .reachable()
+            .min_by_key(|&(pos, d)| (pos.manhattan(&to), d))?;
+        if goal == from {
+            return None;
+        }
+        self.distances([goal]).step(from)
+    }
+}
@@ -1,14 +1,25 @@
+mod allocator;
+mod distance;
+mod recycler;
+// the bot does not look ahead yet, only the tests play turns
+#[cfg(test)]
+mod simulator;
+mod stage;
 #[cfg(test)]
 mod tests;
+mod voronoi;
 
+use std::cmp::Reverse;
+use std::env;
 use std::fmt;
-use std::io;
 
-macro_rules! parse_input {
-    ($x:expr, $t:ident) => {
-        $x.trim().parse::<$t>().unwrap()
-    };
-}
+use allocator::allocate_moves;
+use cg_common::{Console, DistanceMap, FromLine, Grid2D, ParseError, Vec2i, Words};
+
+/// Tile owners, the grid is seen from our side
+const ME: i32 = 1;
+const FOE: i32 = 0;
+const NEUTRAL: i32 = -1;
 
 #[derive(Debug, Clone, Copy, Default)]
 struct Cell {
@@ -21,22 +32,23 @@ struct Cell {
     in_recycler_range: bool,
 }
 
-impl Cell {
-    fn update_from_stdin(&mut self) {
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        self.scrap_amount = parse_input!(inputs[0], u32);
-        self.owner = parse_input!(inputs[1], i32); // 1 = me, 0 = foe, -1 = neutral
-        self.units = parse_input!(inputs[2], u32);
-        self.recycler = parse_input!(inputs[3], i32) == 1;
-        self.can_build = parse_input!(inputs[4], i32) == 1;
-        self.can_spawn = parse_input!(inputs[5], i32) == 1;
-        self.in_recycler_range = parse_input!(inputs[6], i32) == 1;
+impl FromLine for Cell {
+    fn from_line(words: &mut Words) -> Result<Self, ParseError> {
+        Ok(Self {
+            scrap_amount: words.field("scrap_amount")?,
+            owner: words.field("owner")?,
+            units: words.field("units")?,
+            recycler: words.flag("recycler")?,
+            can_build: words.flag("can_build")?,
+            can_spawn: words.flag("can_spawn")?,
+            in_recycler_range: words.flag("in_recycler_range")?,
+        })
     }
+}
 
+impl Cell {
     fn is_mine(&self) -> bool {
-        self.owner == 1
+        self.owner == ME
     }
 }
 
@@ -44,233 +56,207 @@ impl Cell {
 struct Grid {
     my_matter: u32,
     enemy_matter: u32,
-    dim: (u32, u32),
-    grid: Vec<Cell>,
+    cells: Grid2D<Cell>,
     mine: Vec<usize>,
-    others: Vec<(u32, u32)>,
+    others: Vec<Vec2i>,
 }
 
 impl Grid {
-    fn new(width: u32, height: u32) -> Self {
+    fn new(width: usize, height: usize) -> Self {
         Self {
             my_matter: 10,
             enemy_matter: 10,
-            dim: (width, height),
-            grid: vec![Cell::default(); (width * height) as usize],
-            mine: Vec::with_capacity((width * height) as usize),
-            others: Vec::with_capacity((width * height) as usize),
+            cells: Grid2D::new(width, height, Cell::default()),
+            mine: Vec::with_capacity(width * height),
+            others: Vec::with_capacity(width * height),
         }
     }
 
-    fn from_stdin() -> Self {
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        let width = parse_input!(inputs[0], u32);
-        let height = parse_input!(inputs[1], u32);
-        Self::new(width, height)
+    fn from_input(console: &mut Console) -> Result<Self, ParseError> {
+        let mut inputs = console.words("size")?;
+        let width = inputs.field("width")?;
+        let height = inputs.field("height")?;
+        Ok(Self::new(width, height))
     }
 
-    fn update_from_stdin(&mut self) {
+    /// Reads the turn, `false` once the input has ended
+    fn update_from_input(&mut self, console: &mut Console) -> Result<bool, ParseError> {
         // update currency
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        self.my_matter = parse_input!(inputs[0], u32);
-        self.enemy_matter = parse_input!(inputs[1], u32);
-        // clear lists
+        let Some(mut inputs) = console.next_words()? else {
+            return Ok(false);
+        };
+        self.my_matter = inputs.field("my_matter")?;
+        self.enemy_matter = inputs.field("opp_matter")?;
+        // update cells
+        for i in 0..self.cells.len() {
+            self.cells[i] = console.read()?;
+        }
+        self.index_cells();
+        Ok(true)
+    }
+
+    /// Rebuilds the lists of our tiles and the other ones
+    fn index_cells(&mut self) {
         self.mine.clear();
         self.others.clear();
-
-        // update cells
-        for (i, cell) in self.grid.iter_mut().enumerate() {
-            cell.update_from_stdin();
-            if cell.is_mine() {
+        for i in 0..self.cells.len() {
+            if self.cells[i].is_mine() {
                 self.mine.push(i);
             } else {
-                let x = i as u32 % self.dim.0;
-                let y = i as u32 / self.dim.0;
-                self.others.push((x, y));
+                self.others.push(self.cells.pos_of(i));
             }
         }
     }
 
+    /// Left, right, top and bottom neighbours, `None` outside of the grid
     fn get_neighbours(&self, index: usize) -> [Option<usize>; 4] {
-        let mut ret = [None; 4];
-        let x = index as u32 % self.dim.0;
-        let y = index as u32 / self.dim.0;
-        //Left
-        if x > 0 {
-            ret[0] = Some(index - 1);
-        }
-        //Right
-        if x < self.dim.0 - 1 {
-            ret[1] = Some(index + 1);
-        }
-        // Top
-        if y > 0 {
-            ret[2] = Some(index - self.dim.0 as usize);
-        }
-        // Bottom
-        if y < self.dim.1 - 1 {
-            ret[3] = Some(index + self.dim.0 as usize);
-        }
-        ret
+        let pos = self.cells.pos_of(index);
+        pos.neighbours().map(|n| self.cells.index_of(n))
     }
+}
 
-    fn get_xy(&self, index: usize) -> (u32, u32) {
-        let x = index as u32 % self.dim.0;
-        let y = index as u32 / self.dim.0;
-        (x, y)
-    }
+/// Walking distances used by the decisions of a turn
+struct Distances {
+    /// From the closest tile we don't own yet
+    frontier: DistanceMap,
+    /// From the closest enemy unit
+    enemy: DistanceMap,
 }
 
-/// This function aims to build at such tile that the recycler doesn't
-/// disappear before all neighbouring tiles are without scrap
-/// probably not useful from a strategical point of view?
-fn check_build(grid: &Grid) -> Option<Action> {
-    if grid.my_matter >= 10 {
-        for &i in grid.mine.iter() {
-            if grid.grid[i].can_build {
-                let can = grid.get_neighbours(i).iter().all(|x| {
-                    if let Some(idx) = x {
-                        grid.grid[*idx].scrap_amount <= grid.grid[i].scrap_amount
-                    } else {
-                        false
-                    }
-                });
-                if can {
-                    let xy = grid.get_xy(i);
-                    return Some(Action::Build(xy.0, xy.1));
-                }
-            }
+impl Distances {
+    fn new(grid: &Grid) -> Self {
+        let frontier = grid
+            .others
+            .iter()
+            .copied()
+            .filter(|&pos| grid.cells[pos].walkable());
+        let enemy = grid
+            .cells
+            .positions()
+            .filter(|&pos| grid.cells[pos].owner == FOE && grid.cells[pos].units > 0);
+        Self {
+            frontier: grid.distances(frontier),
+            enemy: grid.distances(enemy),
         }
     }
-    None
 }
 
-fn dist_squared(from: (u32, u32), to: (u32, u32)) -> u32 {
-    let dx = to.0 as i32 - from.0 as i32;
-    let dy = to.1 as i32 - from.1 as i32;
-    (dx * dx + dy * dy) as u32
-}
-/// First attemt at spawning
-/// General idea is to spawn at a tile closest to most empty and enemy tiles
-fn check_spawn(grid: &Grid) -> Option<Action> {
-    if grid.my_matter >= 10 {
-        let mut max_other = ((0, 0), u32::MAX);
-        for &i in grid.mine.iter() {
-            if grid.grid[i].can_spawn {
-                let xy = grid.get_xy(i);
-                let td: u32 = grid
-                    .others
-                    .iter()
-                    .fold(0, |acc, &pos| acc + dist_squared(xy, pos));
-                if td < max_other.1 {
-                    max_other.0 = xy;
-                    max_other.1 = td;
-                }
-            }
-        }
-        if max_other.1 < u32::MAX {
-            return Some(Action::Spawn(1, max_other.0 .0, max_other.0 .1));
-        }
+/// Matter paid for a recycler or a unit
+const COST: u32 = 10;
+
+/// Matter a recycler has to bring in to be built for the income alone
+const MIN_YIELD: u32 = 30;
+
+/// Builds the recycler with the best plan: a wall winning territory, or
+/// one paying for itself three times that only gives up the tiles turning to grass
+fn check_build(grid: &Grid) -> Option<Action> {
+    if grid.my_matter < COST {
+        return None;
     }
-    None
+    grid.plan_recyclers()
+        .into_iter()
+        .find(|plan| {
+            let cut_off = -plan.territory - plan.grass.len() as i32;
+            plan.territory > 0 || (cut_off <= 0 && plan.matter >= MIN_YIELD)
+        })
+        .map(|plan| Action::Build(plan.pos))
 }
 
-/// First attemt at moving
-/// General idea is to move to a tile closest to most empty and enemy tiles
-fn check_move(grid: &Grid, from: (u32, u32), amount: u32) -> Option<Action> {
-    if grid.my_matter >= 10 {
-        let mut max_other = ((0, 0), u32::MAX);
-        for &xy in grid.others.iter() {
-            let td: u32 = grid
-                .others
-                .iter()
-                .fold(0, |acc, &pos| acc + dist_squared(xy, pos));
-            if td < max_other.1 {
-                max_other.0 = xy;
-                max_other.1 = td;
-            }
-        }
-
-        if max_other.1 < u32::MAX {
-            return Some(Action::Move(
-                amount,
-                from.0,
-                from.1,
-                max_other.0 .0,
-                max_other.0 .1,
-            ));
-        }
+/// Spawns a unit on the tile closest to the tiles we don't own yet, the one
+/// closest to the enemy units and then winning the most territory on a tie
+fn check_spawn(grid: &Grid, distances: &Distances) -> Option<Action> {
+    if grid.my_matter < COST {
+        return None;
     }
-    None
+    let mut staged = grid.clone();
+    grid.mine
+        .iter()
+        .map(|&i| grid.cells.pos_of(i))
+        .filter(|&pos| grid.cells[pos].can_spawn)
+        .filter_map(|pos| {
+            let frontier = distances.frontier.get(pos)?;
+            let enemy = distances.enemy.get(pos).unwrap_or(u32::MAX);
+            staged.cells[pos].units += 1;
+            let score = staged.evaluate();
+            staged.cells[pos].units -= 1;
+            Some((frontier, enemy, Reverse(score), pos))
+        })
+        .min()
+        .map(|(_, _, _, pos)| Action::Spawn(1, pos))
+}
+
+/// Moves the stack one step towards the closest tile we don't own yet,
+/// nothing when none can be walked to
+fn check_move(distances: &Distances, from: Vec2i, amount: u32) -> Option<Action> {
+    let next = distances.frontier.step(from)?;
+    Some(Action::Move(amount, from, next))
 }
 
 #[derive(Debug)]
 enum Action {
     Wait,
-    Move(u32, u32, u32, u32, u32),
-    Build(u32, u32),
-    Spawn(u32, u32, u32),
+    Move(u32, Vec2i, Vec2i),
+    Build(Vec2i),
+    Spawn(u32, Vec2i),
 }
 
 impl fmt::Display for Action {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
             Action::Wait => write!(f, "WAIT"),
-            Action::Move(amount, from_x, from_y, to_x, to_y) => {
-                write!(f, "MOVE {} {} {} {} {}", amount, from_x, from_y, to_x, to_y)
-            }
-            Action::Build(x, y) => write!(f, "BUILD {} {}", x, y),
-            Action::Spawn(amount, x, y) => write!(f, "SPAWN {} {} {}", amount, x, y),
+            Action::Move(amount, from, to) => write!(f, "MOVE {} {} {}", amount, from, to),
+            Action::Build(pos) => write!(f, "BUILD {}", pos),
+            Action::Spawn(amount, pos) => write!(f, "SPAWN {} {}", amount, pos),
         }
     }
 }
 
-fn main() {
-    let mut grid = Grid::from_stdin();
+/// Commands of the whole turn on one line
+fn play(grid: &Grid) -> String {
+    let distances = Distances::new(grid);
+    // our own actions go on a copy of the grid so the later decisions see
+    // what is left
+    let mut staged = grid.clone();
     let mut action_set: Vec<Action> = Vec::new();
-    let mut action_string: String = String::new();
-    // game loop
-    loop {
-        grid.update_from_stdin();
+    // move first, stacks split between the tiles to take
+    let moves = allocate_moves(grid, &distances);
+    action_set.extend(moves.into_iter().filter(|a| staged.stage(a)));
+    // build to block or to collect matter
+    action_set.extend(check_build(&staged).filter(|a| staged.stage(a)));
+    // spawn if there's enough credits
+    action_set.extend(check_spawn(&staged, &distances).filter(|a| staged.stage(a)));
+    // spawn decision or move decision
+    if action_set.is_empty() {
+        return format!("{}\n", Action::Wait);
+    }
+    let mut action_string = String::new();
+    for action in action_set.iter() {
+        action_string.push_str(&format!("{};", action));
+    }
+    action_string.push('\n');
+    action_string
+}
 
-        // Write an action using println!("message...");
+fn main() {
+    // `replay <file> [turn]` stops after the given turn
+    let args = env::args().collect::<Vec<_>>();
+    let mut console = Console::from_args(&args[1..]).unwrap();
+    let last_turn = match args.get(1).map(String::as_str) {
+        Some("replay") => args.get(3).and_then(|t| t.parse::<u32>().ok()),
+        _ => None,
+    };
+    let mut grid = Grid::from_input(&mut console).unwrap_or_else(|e| e.exit());
+    let mut turn = 0;
+    // game loop
+    while grid
+        .update_from_input(&mut console)
+        .unwrap_or_else(|e| e.exit())
+    {
+        turn += 1;
         // To debug: eprintln!("Debug message...");
-
-        action_set.clear();
-        // move first
-        for &i in grid.mine.iter() {
-            if grid.grid[i].units > 0 {
-                let from_xy = grid.get_xy(i);
-                if let Some(action) = check_move(&grid, from_xy, grid.grid[i].units) {
-                    action_set.push(action);
-                    // should update grid here to enable build and spawn in newly free locations
-                }
-            }
-        }
-        // update grid with moves
-        // build to block?
-        // spawn if there's enough credits
-        if let Some(action) = check_spawn(&grid) {
-            action_set.push(action);
-        }
-        // if let Some(action) = check_build(&grid) {
-        //     action_set.push(action);
-        //     // update grid with the build action
-        //     // check for additional builds?
-        // }
-        // spawn decision or move decision
-        if action_set.is_empty() {
-            println!("{}", Action::Wait);
-            continue;
-        }
-        action_string.clear();
-        for action in action_set.iter() {
-            print!("{};", action);
+        console.write_turn(&play(&grid));
+        if last_turn == Some(turn) {
+            break;
         }
-        println!();
     }
 }
@@ -0,0 +1,78 @@
+//! Recycler placement. A recycler takes one scrap a turn from its tile and
+//! the four around it until its own tile turns to grass, and every
+//! neighbour with as little scrap goes to grass along with it.
+
+use cg_common::Vec2i;
+
+use crate::voronoi::{Territory, TILE_VALUE};
+use crate::{Cell, Grid, NEUTRAL};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecyclerPlan {
+    pub pos: Vec2i,
+    /// Turns before the recycler's own tile turns to grass
+    pub lifetime: u32,
+    /// Matter collected over the lifetime, tiles some recycler already
+    /// reaches don't give anything more
+    pub matter: u32,
+    /// Tiles that end up as grass, the recycler's own first
+    pub grass: Vec<Vec2i>,
+    /// Tiles won over the opponent once the grass has grown, negative when
+    /// it walls off our own territory
+    pub territory: i32,
+}
+
+impl RecyclerPlan {
+    pub fn score(&self) -> i32 {
+        self.matter as i32 + self.territory * TILE_VALUE
+    }
+}
+
+fn balance(territory: &Territory) -> i32 {
+    territory.mine as i32 - territory.theirs as i32
+}
+
+impl Grid {
+    fn plan_recycler(&self, index: usize, before: &Territory) -> RecyclerPlan {
+        let lifetime = self.cells[index].scrap_amount;
+        let mut matter = 0;
+        let mut grass = Vec::new();
+        let around = self.get_neighbours(index).into_iter().flatten();
+        for i in std::iter::once(index).chain(around) {
+            let cell = &self.cells[i];
+            if !cell.in_recycler_range {
+                matter += cell.scrap_amount.min(lifetime);
+            }
+            if cell.scrap_amount <= lifetime {
+                grass.push(self.cells.pos_of(i));
+            }
+        }
+        let mut staged = self.clone();
+        for &pos in grass.iter() {
+            staged.cells[pos] = Cell {
+                owner: NEUTRAL,
+                ..Cell::default()
+            };
+        }
+        RecyclerPlan {
+            pos: self.cells.pos_of(index),
+            lifetime,
+            matter,
+            grass,
+            territory: balance(&staged.territory()) - balance(before),
+        }
+    }
+
+    /// Plans for every tile we can build on, best score first
+    pub fn plan_recyclers(&self) -> Vec<RecyclerPlan> {
+        let before = self.territory();
+        let mut plans = self
+            .mine
+            .iter()
+            .filter(|&&i| self.cells[i].can_build)
+            .map(|&i| self.plan_recycler(i, &before))
+            .collect::<Vec<_>>();
+        plans.sort_by_key(|plan| std::cmp::Reverse(plan.score()));
+        plans
+    }
+}
@@ -1,29 +1,225 @@
-#[derive(Debug, Clone, Copy, Default)]
-struct GameState {
-    my_matter: u32,
-    enemy_matter: u32,
+//! Forward model of a Keep Off The Grass turn on the bot's own `Grid`.
+//!
+//! The grid is seen from our side, owner 1 is us and 0 the opponent. Actions
+//! are resolved in the referee's order: builds, moves and spawns, fights,
+//! marking, recycling, grass removal and income.
+
+use cg_common::{Grid2D, Vec2i};
+
+use crate::{Action, Cell, Grid, COST, FOE, ME, NEUTRAL};
+
+/// Matter every player gets at the end of the turn
+const INCOME: u32 = 10;
+
+#[derive(Debug, Clone)]
+pub struct GameState {
+    pub grid: Grid,
+    /// Turns played so far
+    pub turn: u32,
+    /// Turns since the last change of scrap or owner
+    pub stale: u32,
 }
 
 impl GameState {
-    fn update_from_stdin(&mut self) {
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        self.my_matter = parse_input!(inputs[0], u32);
-        self.enemy_matter = parse_input!(inputs[1], u32);
+    pub const MAX_TURNS: u32 = 200;
+    pub const MAX_STALE: u32 = 20;
+
+    pub fn new(grid: Grid, turn: u32) -> Self {
+        Self {
+            grid,
+            turn,
+            stale: 0,
+        }
+    }
+
+    fn matter(&self, owner: i32) -> u32 {
+        if owner == ME {
+            self.grid.my_matter
+        } else {
+            self.grid.enemy_matter
+        }
     }
 
-    fn update() {
+    fn matter_mut(&mut self, owner: i32) -> &mut u32 {
+        if owner == ME {
+            &mut self.grid.my_matter
+        } else {
+            &mut self.grid.enemy_matter
+        }
+    }
+
+    /// Plays one turn with our actions and the opponent's, invalid actions
+    /// are skipped like the referee does
+    pub fn update(&mut self, mine: &[Action], theirs: &[Action]) {
+        let players = [(ME, mine), (FOE, theirs)];
+        let before = self.grid.cells.clone();
+
         // resolve BUILD
-        // MOVE & SPAWN
-        // Remove colliding robots
+        for (owner, actions) in players {
+            for action in actions {
+                if let Action::Build(pos) = *action {
+                    self.build(owner, pos);
+                }
+            }
+        }
 
-        // Mark tiles
+        // MOVE & SPAWN, units arrive once everybody has left
+        let mut arrivals = self.grid.cells.map(|_| [0; 2]);
+        for (owner, actions) in players {
+            for action in actions {
+                match *action {
+                    Action::Move(amount, from, to) => {
+                        self.walk(owner, amount, from, to, &mut arrivals)
+                    }
+                    Action::Spawn(amount, pos) => self.spawn(owner, amount, pos, &mut arrivals),
+                    Action::Wait | Action::Build(_) => {}
+                }
+            }
+        }
 
-        // Recyclers reduce scraps of tiles
-        // Tiles with 0 scraps -> Grass -> Remove units and structures
+        // Remove colliding robots and mark tiles
+        self.fight(&arrivals);
+        // Recyclers reduce scraps of tiles, tiles with 0 scraps turn to grass
+        self.recycle();
         // Currency update
-        // Check game end
+        self.grid.my_matter += INCOME;
+        self.grid.enemy_matter += INCOME;
+
+        let changed = before
+            .iter()
+            .zip(self.grid.cells.iter())
+            .any(|(a, b)| a.scrap_amount != b.scrap_amount || a.owner != b.owner);
+        self.stale = if changed { 0 } else { self.stale + 1 };
+        self.turn += 1;
+        self.refresh();
+    }
+
+    fn build(&mut self, owner: i32, pos: Vec2i) {
+        let Some(cell) = self.grid.cells.get(pos) else {
+            return;
+        };
+        if cell.owner == owner && cell.units == 0 && !cell.recycler && self.matter(owner) >= COST {
+            *self.matter_mut(owner) -= COST;
+            self.grid.cells[pos].recycler = true;
+        }
+    }
+
+    fn walk(
+        &mut self,
+        owner: i32,
+        amount: u32,
+        from: Vec2i,
+        to: Vec2i,
+        arrivals: &mut Grid2D<[u32; 2]>,
+    ) {
+        let Some(cell) = self.grid.cells.get(from) else {
+            return;
+        };
+        let amount = amount.min(cell.units);
+        if cell.owner != owner || amount == 0 || from == to {
+            return;
+        }
+        let Some(next) = self.grid.step(from, to) else {
+            return;
+        };
+        self.grid.cells[from].units -= amount;
+        arrivals[next][owner as usize] += amount;
+    }
+
+    fn spawn(&mut self, owner: i32, amount: u32, pos: Vec2i, arrivals: &mut Grid2D<[u32; 2]>) {
+        let Some(cell) = self.grid.cells.get(pos) else {
+            return;
+        };
+        let amount = amount.min(self.matter(owner) / COST);
+        if cell.owner != owner || cell.recycler || amount == 0 {
+            return;
+        }
+        *self.matter_mut(owner) -= amount * COST;
+        arrivals[pos][owner as usize] += amount;
+    }
+
+    /// Units of both sides remove each other one for one, the side left
+    /// standing owns the tile
+    fn fight(&mut self, arrivals: &Grid2D<[u32; 2]>) {
+        for (cell, arrived) in self.grid.cells.iter_mut().zip(arrivals.iter()) {
+            let mut units = *arrived;
+            if cell.owner != NEUTRAL {
+                units[cell.owner as usize] += cell.units;
+            }
+            let fallen = units[0].min(units[1]);
+            units[0] -= fallen;
+            units[1] -= fallen;
+            cell.units = units[0] + units[1];
+            if units[ME as usize] > 0 {
+                cell.owner = ME;
+            } else if units[FOE as usize] > 0 {
+                cell.owner = FOE;
+            }
+        }
+    }
+
+    /// A tile loses one scrap however many recyclers reach it, and every
+    /// player with a recycler next to it gets one matter
+    fn recycle(&mut self) {
+        let cells = &self.grid.cells;
+        let mut recycled = cells.map(|_| [false; 2]);
+        for i in 0..cells.len() {
+            if !cells[i].recycler || cells[i].owner == NEUTRAL {
+                continue;
+            }
+            let owner = cells[i].owner as usize;
+            for n in std::iter::once(i).chain(cells.neighbour_indices(i)) {
+                if cells[n].scrap_amount > 0 {
+                    recycled[n][owner] = true;
+                }
+            }
+        }
+        for (cell, by) in self.grid.cells.iter_mut().zip(recycled.iter()) {
+            if by == &[false; 2] {
+                continue;
+            }
+            cell.scrap_amount -= 1;
+            if by[ME as usize] {
+                self.grid.my_matter += 1;
+            }
+            if by[FOE as usize] {
+                self.grid.enemy_matter += 1;
+            }
+            if cell.scrap_amount == 0 {
+                *cell = Cell {
+                    owner: NEUTRAL,
+                    ..Cell::default()
+                };
+            }
+        }
+    }
+
+    /// Flags the referee would send for the new grid
+    fn refresh(&mut self) {
+        let cells = &self.grid.cells;
+        let in_range = (0..cells.len())
+            .map(|i| {
+                cells[i].scrap_amount > 0
+                    && std::iter::once(i)
+                        .chain(cells.neighbour_indices(i))
+                        .any(|n| cells[n].recycler)
+            })
+            .collect::<Vec<_>>();
+        for (cell, in_range) in self.grid.cells.iter_mut().zip(in_range) {
+            cell.can_spawn = cell.is_mine() && !cell.recycler;
+            cell.can_build = cell.can_spawn && cell.units == 0;
+            cell.in_recycler_range = in_range;
+        }
+        self.grid.index_cells();
+    }
+
+    /// Tiles owned by the opponent and by us
+    pub fn tiles(&self) -> [usize; 2] {
+        let mut tiles = [0; 2];
+        for cell in self.grid.cells.iter().filter(|c| c.owner != NEUTRAL) {
+            tiles[cell.owner as usize] += 1;
+        }
+        tiles
     }
 
     /// Checks if the game should end
@@ -31,11 +227,17 @@ impl GameState {
     /// - a player no longer controls single tile
     /// - 20 turns have passed without any tile changing scraps or owner
     /// - 200 have concluded
-    fn check_game_end() {
-        // check end
-        // check winner?
+    pub fn check_game_end(&self) -> bool {
+        self.tiles().contains(&0) || self.stale >= Self::MAX_STALE || self.turn >= Self::MAX_TURNS
     }
 
-    /// Player that controls the most tiles
-    fn check_winning_player() {}
+    /// Player that controls the most tiles, `None` on a draw
+    pub fn check_winning_player(&self) -> Option<i32> {
+        let [foe, me] = self.tiles();
+        match me.cmp(&foe) {
+            std::cmp::Ordering::Greater => Some(ME),
+            std::cmp::Ordering::Less => Some(FOE),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
 }
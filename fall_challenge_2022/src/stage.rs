@@ -0,0 +1,64 @@
+//! Our own actions applied to a copy of the grid as they are chosen, so the
+//! later decisions of the turn see the matter, units and tiles left.
+//!
+//! The referee builds before anything moves, so a tile keeps its units for
+//! the build even when they walk away, and a tile our units walk into or
+//! spawn on can't get a recycler. Units on their way are out of the grid
+//! until the next turn.
+
+use crate::{Action, Grid, COST};
+
+impl Grid {
+    /// Applies one of our actions, `false` when it can't be played on top
+    /// of the ones already staged
+    pub fn stage(&mut self, action: &Action) -> bool {
+        match *action {
+            Action::Wait => true,
+            Action::Move(amount, from, to) => {
+                let (Some(cell), Some(_)) = (self.cells.get(from), self.cells.get(to)) else {
+                    return false;
+                };
+                if !cell.is_mine() || amount == 0 || amount > cell.units || from == to {
+                    return false;
+                }
+                self.cells[from].units -= amount;
+                if let Some(step) = self.step(from, to) {
+                    self.cells[step].can_build = false;
+                }
+                true
+            }
+            Action::Build(pos) => {
+                let Some(cell) = self.cells.get(pos) else {
+                    return false;
+                };
+                if !cell.can_build || self.my_matter < COST {
+                    return false;
+                }
+                self.my_matter -= COST;
+                let index = self.cells.index_of(pos).unwrap();
+                let around = self.get_neighbours(index).into_iter().flatten();
+                for i in std::iter::once(index).chain(around) {
+                    self.cells[i].in_recycler_range = self.cells[i].scrap_amount > 0;
+                }
+                let cell = &mut self.cells[pos];
+                cell.recycler = true;
+                cell.can_build = false;
+                cell.can_spawn = false;
+                true
+            }
+            Action::Spawn(amount, pos) => {
+                let Some(cell) = self.cells.get(pos) else {
+                    return false;
+                };
+                if !cell.can_spawn || amount == 0 || self.my_matter < amount * COST {
+                    return false;
+                }
+                self.my_matter -= amount * COST;
+                let cell = &mut self.cells[pos];
+                cell.units += amount;
+                cell.can_build = false;
+                true
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::allocator::allocate_moves;
+use crate::simulator::GameState;
 use crate::*;
 
 #[test]
@@ -46,8 +48,363 @@ fn get_neighbours_of_index() {
 }
 
 #[test]
-fn dist_sq() {
-    let f = (1, 2);
-    let t = (2, 3);
-    assert_eq!(dist_squared(f, t), 2);
+fn cell_line() {
+    let mut words = Words::new(3, "9  1 2 0 1 0 1\r\n");
+    let cell = Cell::from_line(&mut words).unwrap();
+    assert_eq!((cell.scrap_amount, cell.owner, cell.units), (9, 1, 2));
+    assert!(!cell.recycler && cell.can_build && !cell.can_spawn && cell.in_recycler_range);
+    let mut words = Words::new(4, "9 -1 0 0 0 0");
+    let error = Cell::from_line(&mut words).unwrap_err();
+    assert_eq!((error.field, error.line_number), ("in_recycler_range", 4));
+    let mut words = Words::new(5, "9 -1 -2 0 0 0 0");
+    assert_eq!(Cell::from_line(&mut words).unwrap_err().field, "units");
+}
+
+/// Opening of a local match, the bot has to keep making the same decisions
+#[test]
+fn replay_opening() {
+    let mut console = Console::replay(include_str!("../replays/opening.txt"));
+    let mut grid = Grid::from_input(&mut console).unwrap();
+    let mut turns = 0;
+    while grid.update_from_input(&mut console).unwrap() {
+        turns += 1;
+        assert!(console.write_turn(&play(&grid)), "turn {}", turns);
+    }
+    assert_eq!(turns, 3);
+}
+
+/// Neutral tiles from rows of scrap amounts, one digit per tile
+fn board(rows: &[&str]) -> GameState {
+    let mut grid = Grid::new(rows[0].len(), rows.len());
+    let scraps = rows.iter().flat_map(|r| r.chars());
+    for (cell, scrap) in grid.cells.iter_mut().zip(scraps) {
+        *cell = Cell {
+            scrap_amount: scrap.to_digit(10).unwrap(),
+            owner: NEUTRAL,
+            ..Cell::default()
+        };
+    }
+    GameState::new(grid, 0)
+}
+
+fn place(state: &mut GameState, x: i32, y: i32, owner: i32, units: u32) {
+    let cell = &mut state.grid.cells[Vec2i::new(x, y)];
+    cell.owner = owner;
+    cell.units = units;
+}
+
+fn cell(state: &GameState, x: i32, y: i32) -> (i32, u32) {
+    let cell = &state.grid.cells[Vec2i::new(x, y)];
+    (cell.owner, cell.units)
+}
+
+#[test]
+fn sim_moves_and_fights() {
+    let mut state = board(&["555"]);
+    place(&mut state, 0, 0, ME, 3);
+    place(&mut state, 2, 0, FOE, 1);
+    let (left, right) = (Vec2i::new(0, 0), Vec2i::new(2, 0));
+    state.update(
+        &[Action::Move(5, left, right)],
+        &[Action::Move(1, right, left)],
+    );
+    // both stacks meet in the middle, the tiles they left stay owned
+    assert_eq!(cell(&state, 0, 0), (ME, 0));
+    assert_eq!(cell(&state, 1, 0), (ME, 2));
+    assert_eq!(cell(&state, 2, 0), (FOE, 0));
+    assert_eq!((state.grid.my_matter, state.grid.enemy_matter), (20, 20));
+    assert_eq!(state.grid.mine, [0, 1]);
+    assert_eq!(state.grid.others, [right]);
+    assert!(state.grid.cells[0].can_build && !state.grid.cells[1].can_build);
+    assert!(state.grid.cells[1].can_spawn && !state.grid.cells[2].can_spawn);
+    assert_eq!((state.turn, state.stale), (1, 0));
+}
+
+#[test]
+fn sim_moves_around_grass() {
+    let mut state = board(&["505", "555"]);
+    place(&mut state, 0, 0, ME, 1);
+    state.update(&[Action::Move(1, Vec2i::new(0, 0), Vec2i::new(2, 0))], &[]);
+    assert_eq!(cell(&state, 0, 1), (ME, 1));
+    // a target out of reach is only approached
+    let mut state = board(&["505"]);
+    place(&mut state, 0, 0, ME, 1);
+    assert_eq!(state.grid.step(Vec2i::new(0, 0), Vec2i::new(2, 0)), None);
+    state.update(&[Action::Move(1, Vec2i::new(0, 0), Vec2i::new(2, 0))], &[]);
+    assert_eq!(cell(&state, 0, 0), (ME, 1));
+}
+
+#[test]
+fn sim_build_and_recycle() {
+    let mut state = board(&["121"]);
+    place(&mut state, 0, 0, FOE, 1);
+    place(&mut state, 1, 0, ME, 0);
+    let build = Action::Build(Vec2i::new(1, 0));
+    // the opponent can't build under its own unit
+    state.update(&[build], &[Action::Build(Vec2i::new(0, 0))]);
+    // the recycler takes the starting matter, three tiles give it back
+    assert_eq!(state.grid.my_matter, 3 + 10);
+    assert_eq!(state.grid.enemy_matter, 20);
+    let recycler = state.grid.cells[1];
+    assert!(recycler.recycler && recycler.in_recycler_range && !recycler.can_spawn);
+    assert_eq!(recycler.scrap_amount, 1);
+    // the unit went down with the grass
+    assert_eq!(cell(&state, 0, 0), (NEUTRAL, 0));
+    assert_eq!(state.grid.cells[2].scrap_amount, 0);
+    assert!(state.check_game_end());
+    assert_eq!(state.check_winning_player(), Some(ME));
+}
+
+#[test]
+fn sim_spawns_and_shares_recycling() {
+    let mut state = board(&["3333"]);
+    place(&mut state, 0, 0, ME, 0);
+    place(&mut state, 3, 0, FOE, 0);
+    state.grid.my_matter = 25;
+    state.grid.enemy_matter = 0;
+    let spawns = [
+        Action::Spawn(5, Vec2i::new(0, 0)),
+        Action::Spawn(1, Vec2i::new(3, 0)),
+    ];
+    state.update(&spawns, &[]);
+    assert_eq!(cell(&state, 0, 0), (ME, 2));
+    assert_eq!(cell(&state, 3, 0), (FOE, 0));
+    assert_eq!(state.grid.my_matter, 15);
+    // recyclers of both sides next to the same tile take one scrap from it
+    // and both get the matter
+    let mut state = board(&["333"]);
+    place(&mut state, 0, 0, ME, 0);
+    place(&mut state, 2, 0, FOE, 0);
+    state.grid.cells[0].recycler = true;
+    state.grid.cells[2].recycler = true;
+    state.grid.my_matter = 0;
+    state.grid.enemy_matter = 0;
+    state.update(&[], &[]);
+    let scraps = state.grid.cells.iter().map(|c| c.scrap_amount);
+    assert_eq!(scraps.collect::<Vec<_>>(), [2, 2, 2]);
+    assert_eq!((state.grid.my_matter, state.grid.enemy_matter), (12, 12));
+}
+
+#[test]
+fn sim_game_end() {
+    let mut state = board(&["55"]);
+    place(&mut state, 0, 0, ME, 0);
+    place(&mut state, 1, 0, FOE, 0);
+    for _ in 0..GameState::MAX_STALE {
+        assert!(!state.check_game_end());
+        state.update(&[Action::Wait], &[]);
+    }
+    assert!(state.check_game_end());
+    assert_eq!(state.check_winning_player(), None);
+    let mut state = board(&["55"]);
+    place(&mut state, 0, 0, ME, 1);
+    place(&mut state, 1, 0, FOE, 0);
+    state.turn = GameState::MAX_TURNS - 1;
+    let attack = Action::Move(1, Vec2i::new(0, 0), Vec2i::new(1, 0));
+    state.update(&[attack], &[]);
+    assert!(state.check_game_end());
+    assert_eq!(state.check_winning_player(), Some(ME));
+}
+
+#[test]
+fn distances_avoid_grass_and_recyclers() {
+    let mut state = board(&["5505", "5555"]);
+    state.grid.cells[Vec2i::new(1, 1)].recycler = true;
+    let distances = state.grid.distances([Vec2i::new(0, 0)]);
+    assert_eq!(distances.get(Vec2i::new(1, 0)), Some(1));
+    assert_eq!(distances.get(Vec2i::new(0, 1)), Some(1));
+    assert_eq!(distances.get(Vec2i::new(2, 0)), None);
+    assert_eq!(distances.get(Vec2i::new(1, 1)), None);
+    assert_eq!(distances.get(Vec2i::new(3, 0)), None);
+    // walls off the right side, the best the units can do is get closer
+    let step = state.grid.step(Vec2i::new(0, 1), Vec2i::new(3, 0));
+    assert_eq!(step, Some(Vec2i::new(0, 0)));
+    state.grid.cells[Vec2i::new(1, 1)].recycler = false;
+    let step = state.grid.step(Vec2i::new(0, 1), Vec2i::new(3, 0));
+    assert_eq!(step, Some(Vec2i::new(1, 1)));
+}
+
+#[test]
+fn decisions_follow_walking_distances() {
+    let mut state = board(&["505", "555"]);
+    place(&mut state, 0, 0, ME, 2);
+    place(&mut state, 0, 1, ME, 0);
+    // fills in the flags and the lists the referee would send
+    state.update(&[Action::Wait], &[]);
+    let distances = Distances::new(&state.grid);
+    let action = check_move(&distances, Vec2i::new(0, 0), 2).unwrap();
+    assert_eq!(action.to_string(), "MOVE 2 0 0 0 1");
+    let action = check_spawn(&state.grid, &distances).unwrap();
+    assert_eq!(action.to_string(), "SPAWN 1 0 1");
+    // nothing left to take on this side of the grass
+    let mut state = board(&["505"]);
+    place(&mut state, 0, 0, ME, 1);
+    state.update(&[Action::Wait], &[]);
+    let distances = Distances::new(&state.grid);
+    assert!(check_move(&distances, Vec2i::new(0, 0), 1).is_none());
+    assert!(check_spawn(&state.grid, &distances).is_none());
+}
+
+#[test]
+fn territory_split() {
+    let mut state = board(&["55555"]);
+    place(&mut state, 0, 0, ME, 1);
+    place(&mut state, 4, 0, FOE, 1);
+    let territory = state.grid.territory();
+    assert_eq!(
+        territory.owners.iter().copied().collect::<Vec<_>>(),
+        [1, 1, -1, 0, 0]
+    );
+    assert_eq!(
+        (territory.mine, territory.theirs, territory.contested),
+        (2, 2, 1)
+    );
+    // units spawned on an empty tile only move the turn after
+    let mut state = board(&["5555"]);
+    place(&mut state, 0, 0, ME, 0);
+    place(&mut state, 3, 0, FOE, 1);
+    let territory = state.grid.territory();
+    assert_eq!(
+        (territory.mine, territory.theirs, territory.contested),
+        (1, 2, 1)
+    );
+    // grass cuts off the last tile, the recycler stays with its owner
+    let mut state = board(&["5505"]);
+    place(&mut state, 0, 0, ME, 1);
+    place(&mut state, 1, 0, FOE, 0);
+    state.grid.cells[1].recycler = true;
+    let territory = state.grid.territory();
+    assert_eq!(
+        territory.owners.iter().copied().collect::<Vec<_>>(),
+        [1, 0, -1, -1]
+    );
+    assert_eq!(
+        (territory.mine, territory.theirs, territory.contested),
+        (1, 1, 0)
+    );
+}
+
+#[test]
+fn evaluate_candidate_move() {
+    let mut state = board(&["55555"]);
+    place(&mut state, 0, 0, ME, 1);
+    place(&mut state, 4, 0, FOE, 1);
+    assert_eq!(state.grid.evaluate(), 0);
+    let advance = Action::Move(1, Vec2i::new(0, 0), Vec2i::new(4, 0));
+    let mut after = state.clone();
+    after.update(&[advance], &[Action::Wait]);
+    assert_eq!(after.grid.evaluate(), 10);
+    // an extra unit is worth less than a tile
+    place(&mut state, 0, 0, ME, 2);
+    assert_eq!(state.grid.evaluate(), 1);
+}
+
+#[test]
+fn recycler_plans() {
+    let mut state = board(&["242", "131"]);
+    place(&mut state, 1, 0, ME, 0);
+    state.update(&[Action::Wait], &[]);
+    let plans = state.grid.plan_recyclers();
+    assert_eq!(plans.len(), 1);
+    let plan = &plans[0];
+    assert_eq!(
+        (plan.pos, plan.lifetime, plan.matter),
+        (Vec2i::new(1, 0), 4, 4 + 2 + 2 + 3)
+    );
+    let grass = [(1, 0), (0, 0), (2, 0), (1, 1)].map(|(x, y)| Vec2i::new(x, y));
+    assert_eq!(plan.grass, grass);
+    // the only tile we own goes to grass, nothing is left to spread from
+    assert_eq!(plan.territory, -6);
+    // tiles a recycler already reaches don't give more matter
+    state.grid.cells[Vec2i::new(1, 1)].in_recycler_range = true;
+    assert_eq!(state.grid.plan_recyclers()[0].matter, 4 + 2 + 2);
+}
+
+#[test]
+fn recycler_walls_and_income() {
+    // a recycler in the corridor would hand the far side to the opponent
+    let mut state = board(&["5551555"]);
+    place(&mut state, 0, 0, ME, 1);
+    place(&mut state, 3, 0, ME, 0);
+    place(&mut state, 6, 0, FOE, 1);
+    state.update(&[Action::Wait], &[]);
+    let plan = state.grid.plan_recyclers().remove(0);
+    assert_eq!(
+        (plan.pos, plan.matter, plan.territory),
+        (Vec2i::new(3, 0), 3, -2)
+    );
+    assert!(check_build(&state.grid).is_none());
+    // away from the front a rich tile pays for itself
+    let mut state = board(&["9999999", "8999999", "9999999"]);
+    place(&mut state, 0, 1, ME, 0);
+    place(&mut state, 1, 1, ME, 1);
+    place(&mut state, 6, 1, FOE, 1);
+    state.update(&[Action::Wait], &[]);
+    let build = check_build(&state.grid).unwrap();
+    assert_eq!(build.to_string(), "BUILD 0 1");
+}
+
+fn moves(state: &GameState) -> Vec<String> {
+    let distances = Distances::new(&state.grid);
+    let moves = allocate_moves(&state.grid, &distances);
+    moves.iter().map(|m| m.to_string()).collect()
+}
+
+#[test]
+fn allocator_splits_stacks() {
+    let mut state = board(&["555"]);
+    place(&mut state, 1, 0, ME, 2);
+    state.update(&[Action::Wait], &[]);
+    assert_eq!(moves(&state), ["MOVE 1 1 0 0 0", "MOVE 1 1 0 2 0"]);
+    // far targets are walked to one step at a time
+    let mut state = board(&["5555"]);
+    place(&mut state, 0, 0, ME, 1);
+    place(&mut state, 1, 0, ME, 0);
+    place(&mut state, 2, 0, ME, 0);
+    state.update(&[Action::Wait], &[]);
+    assert_eq!(moves(&state), ["MOVE 1 0 0 1 0"]);
+}
+
+#[test]
+fn allocator_accounts_for_enemies() {
+    // the tile next to the enemy needs enough units to win the fight
+    let mut state = board(&["5555"]);
+    place(&mut state, 1, 0, ME, 3);
+    place(&mut state, 3, 0, FOE, 2);
+    state.update(&[Action::Wait], &[]);
+    assert_eq!(moves(&state), ["MOVE 1 1 0 0 0", "MOVE 2 1 0 2 0"]);
+    // one unit stays home to face the enemy next door
+    let mut state = board(&["5555"]);
+    place(&mut state, 1, 0, ME, 3);
+    place(&mut state, 2, 0, FOE, 1);
+    state.update(&[Action::Wait], &[]);
+    assert_eq!(moves(&state), ["MOVE 1 1 0 0 0", "MOVE 1 1 0 2 0"]);
+}
+
+#[test]
+fn staged_actions_reserve_matter_and_tiles() {
+    let mut state = board(&["5555", "5555"]);
+    place(&mut state, 0, 0, ME, 2);
+    place(&mut state, 1, 0, ME, 0);
+    place(&mut state, 0, 1, ME, 0);
+    state.update(&[Action::Wait], &[]);
+    let mut staged = state.grid.clone();
+    let (home, next, side) = (Vec2i::new(0, 0), Vec2i::new(1, 0), Vec2i::new(0, 1));
+    assert!(!staged.stage(&Action::Move(3, home, next)));
+    assert!(staged.stage(&Action::Move(2, home, Vec2i::new(3, 0))));
+    // units walking in or away both stand in the way of a recycler
+    assert!(!staged.stage(&Action::Build(next)));
+    assert!(!staged.stage(&Action::Build(home)));
+    assert!(staged.stage(&Action::Build(side)));
+    assert_eq!(staged.my_matter, 10);
+    assert!(staged.cells[home].in_recycler_range);
+    assert!(!staged.cells[Vec2i::new(1, 0)].in_recycler_range);
+    // no spawning on the new recycler nor with matter already spent
+    assert!(!staged.stage(&Action::Spawn(1, side)));
+    assert!(!staged.stage(&Action::Spawn(2, next)));
+    assert!(staged.stage(&Action::Spawn(1, next)));
+    assert_eq!((staged.my_matter, staged.cells[next].units), (0, 1));
+    assert!(!staged.stage(&Action::Spawn(1, home)));
+    // the input grid is left alone
+    assert_eq!(state.grid.my_matter, 20);
 }
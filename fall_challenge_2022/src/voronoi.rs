@@ -0,0 +1,92 @@
+//! Voronoi split of the grid: every tile goes to the side whose units get
+//! there first, tiles both sides reach on the same turn are contested.
+
+use cg_common::{Grid2D, Vec2i};
+
+use crate::{Grid, FOE, ME, NEUTRAL};
+
+/// Weight of a tile of territory against a unit in `Grid::evaluate`
+pub const TILE_VALUE: i32 = 10;
+
+#[derive(Debug, Clone)]
+pub struct Territory {
+    /// Side expected to end up with each tile, `NEUTRAL` for grass, tiles
+    /// out of reach and contested ones
+    pub owners: Grid2D<i32>,
+    pub mine: usize,
+    pub theirs: usize,
+    pub contested: usize,
+}
+
+impl Grid {
+    /// Turns the units of `owner` need to reach every tile. A unit spawned
+    /// on an owned tile only moves the turn after.
+    fn arrival(&self, owner: i32) -> Grid2D<Option<u32>> {
+        let owned = |pos: Vec2i| self.cells[pos].owner == owner && self.cells[pos].walkable();
+        let units = self
+            .cells
+            .positions()
+            .filter(|&p| owned(p) && self.cells[p].units > 0);
+        let by_units = self.distances(units);
+        let by_spawns = self.distances(self.cells.positions().filter(|&p| owned(p)));
+        let cells = by_units
+            .grid()
+            .iter()
+            .zip(by_spawns.grid().iter())
+            .map(|(&u, &s)| match (u, s.map(|d| d + 1)) {
+                (Some(u), Some(s)) => Some(u.min(s)),
+                (u, s) => u.or(s),
+            })
+            .collect();
+        Grid2D::from_cells(self.cells.width(), self.cells.height(), cells)
+    }
+
+    /// Splits the tiles between both sides, recyclers stay with their owner
+    pub fn territory(&self) -> Territory {
+        let mine = self.arrival(ME);
+        let theirs = self.arrival(FOE);
+        let mut territory = Territory {
+            owners: self.cells.map(|_| NEUTRAL),
+            mine: 0,
+            theirs: 0,
+            contested: 0,
+        };
+        for i in 0..self.cells.len() {
+            let cell = &self.cells[i];
+            let owner = if cell.scrap_amount == 0 {
+                NEUTRAL
+            } else if cell.recycler {
+                cell.owner
+            } else {
+                match (mine[i], theirs[i]) {
+                    (Some(m), Some(t)) if m == t => {
+                        territory.contested += 1;
+                        NEUTRAL
+                    }
+                    (Some(m), Some(t)) if m < t => ME,
+                    (Some(_), None) => ME,
+                    (_, Some(_)) => FOE,
+                    (None, None) => NEUTRAL,
+                }
+            };
+            match owner {
+                ME => territory.mine += 1,
+                FOE => territory.theirs += 1,
+                _ => {}
+            }
+            territory.owners[i] = owner;
+        }
+        territory
+    }
+
+    /// Score of the grid for us, higher is better. Compare it before and
+    /// after a candidate action to see what the action wins.
+    pub fn evaluate(&self) -> i32 {
+        let territory = self.territory();
+        let mut units = 0;
+        for cell in self.cells.iter().filter(|c| c.owner != NEUTRAL) {
+            units += if cell.is_mine() { 1 } else { -1 } * cell.units as i32;
+        }
+        (territory.mine as i32 - territory.theirs as i32) * TILE_VALUE + units
+    }
+}
@@ -0,0 +1,390 @@
+//! Fall Challenge 2022 (Keep Off The Grass) stand-in referee.
+//!
+//! Maps are generated from the seed and mirrored left to right. The turn
+//! follows the official order: builds, moves and spawns, fights, ownership,
+//! recycling, grass removal and income.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Duration;
+
+use crate::{Outcome, Referee, Rng};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Cell {
+    scrap: i32,
+    owner: Option<usize>,
+    units: i32,
+    recycler: bool,
+}
+
+impl Cell {
+    fn walkable(&self) -> bool {
+        self.scrap > 0 && !self.recycler
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Move(i32, usize, usize),
+    Build(usize),
+    Spawn(i32, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct FallChallenge2022 {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    matter: [i32; 2],
+    turn: u32,
+    /// Turns since the last change of scrap or ownership
+    stale: u32,
+    disqualified: [bool; 2],
+}
+
+impl FallChallenge2022 {
+    const MAX_TURNS: u32 = 200;
+    const MAX_STALE: u32 = 20;
+    const START_MATTER: i32 = 10;
+    const INCOME: i32 = 10;
+    const COST: i32 = 10;
+
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let width = rng.int(12, 24) as usize;
+        let height = rng.int(6, 12) as usize;
+        let mut cells = vec![Cell::default(); width * height];
+        for y in 0..height {
+            for x in 0..width.div_ceil(2) {
+                let scrap = if rng.int(0, 9) < 2 { 0 } else { rng.int(1, 10) };
+                cells[y * width + x].scrap = scrap;
+                cells[y * width + width - 1 - x].scrap = scrap;
+            }
+        }
+        let mut game = Self {
+            width,
+            height,
+            cells,
+            matter: [Self::START_MATTER; 2],
+            turn: 0,
+            stale: 0,
+            disqualified: [false; 2],
+        };
+        let x = rng.int(1, (width / 3) as i32 - 1) as usize;
+        let y = rng.int(1, height as i32 - 2) as usize;
+        for (player, start) in [(0, y * width + x), (1, y * width + width - 1 - x)] {
+            game.cells[start].owner = Some(player);
+            game.cells[start].scrap = game.cells[start].scrap.max(1);
+            for n in game.neighbours(start) {
+                let cell = &mut game.cells[n];
+                cell.scrap = cell.scrap.max(1);
+                cell.owner = Some(player);
+                cell.units = 1;
+            }
+        }
+        game
+    }
+
+    fn neighbours(&self, i: usize) -> Vec<usize> {
+        let (x, y) = (i % self.width, i / self.width);
+        let mut ret = Vec::with_capacity(4);
+        if x > 0 {
+            ret.push(i - 1);
+        }
+        if x + 1 < self.width {
+            ret.push(i + 1);
+        }
+        if y > 0 {
+            ret.push(i - self.width);
+        }
+        if y + 1 < self.height {
+            ret.push(i + self.width);
+        }
+        ret
+    }
+
+    fn index(&self, x: &str, y: &str) -> Option<usize> {
+        let x = x.parse::<usize>().ok()?;
+        let y = y.parse::<usize>().ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn parse_order(&self, words: &[&str]) -> Option<Order> {
+        match words {
+            ["MOVE", n, fx, fy, tx, ty] => Some(Order::Move(
+                n.parse().ok()?,
+                self.index(fx, fy)?,
+                self.index(tx, ty)?,
+            )),
+            ["BUILD", x, y] => Some(Order::Build(self.index(x, y)?)),
+            ["SPAWN", n, x, y] => Some(Order::Spawn(n.parse().ok()?, self.index(x, y)?)),
+            _ => None,
+        }
+    }
+
+    /// Actions of one output line, invalid ones are skipped like the
+    /// official referee does
+    fn parse(&self, line: &str) -> Vec<Order> {
+        line.split(';')
+            .filter_map(|action| {
+                let words = action.split_whitespace().collect::<Vec<_>>();
+                self.parse_order(&words)
+            })
+            .collect()
+    }
+
+    /// Walking distance between `target` and every cell
+    fn distances(&self, target: usize) -> Vec<i32> {
+        let mut dist = vec![i32::MAX; self.cells.len()];
+        let mut queue = VecDeque::new();
+        dist[target] = 0;
+        queue.push_back(target);
+        while let Some(i) = queue.pop_front() {
+            for n in self.neighbours(i) {
+                if dist[n] == i32::MAX && self.cells[n].walkable() {
+                    dist[n] = dist[i] + 1;
+                    queue.push_back(n);
+                }
+            }
+        }
+        dist
+    }
+
+    /// First step from `from` towards `to`, aiming for the reachable cell
+    /// closest to `to` when the target itself can't be walked to
+    fn step(&self, from: usize, to: usize) -> Option<usize> {
+        let reach = self.distances(from);
+        let manhattan = |i: usize| {
+            (i % self.width).abs_diff(to % self.width) + (i / self.width).abs_diff(to / self.width)
+        };
+        let goal = (0..self.cells.len())
+            .filter(|&i| reach[i] != i32::MAX)
+            .min_by_key(|&i| (manhattan(i), reach[i], i))?;
+        if goal == from {
+            return None;
+        }
+        let dist = self.distances(goal);
+        self.neighbours(from)
+            .into_iter()
+            .filter(|&n| self.cells[n].walkable() && dist[n] < dist[from])
+            .min_by_key(|&n| (dist[n], n))
+    }
+
+    fn apply(&mut self, orders: [Vec<Order>; 2]) {
+        // builds use the state at the start of the turn
+        for (player, list) in orders.iter().enumerate() {
+            for order in list.iter() {
+                let Order::Build(i) = *order else {
+                    continue;
+                };
+                let cell = self.cells[i];
+                if cell.owner == Some(player)
+                    && cell.units == 0
+                    && !cell.recycler
+                    && self.matter[player] >= Self::COST
+                {
+                    self.matter[player] -= Self::COST;
+                    self.cells[i].recycler = true;
+                }
+            }
+        }
+
+        // moves and spawns are simultaneous, arrivals are added afterwards
+        let mut arrivals = vec![[0; 2]; self.cells.len()];
+        for (player, list) in orders.iter().enumerate() {
+            for order in list.iter() {
+                match *order {
+                    Order::Move(n, from, to) => {
+                        if self.cells[from].owner != Some(player) || from == to {
+                            continue;
+                        }
+                        let n = n.min(self.cells[from].units);
+                        if n <= 0 {
+                            continue;
+                        }
+                        let Some(next) = self.step(from, to) else {
+                            continue;
+                        };
+                        self.cells[from].units -= n;
+                        arrivals[next][player] += n;
+                    }
+                    Order::Spawn(n, i) => {
+                        let n = n.min(self.matter[player] / Self::COST);
+                        let cell = self.cells[i];
+                        if n <= 0 || cell.owner != Some(player) || cell.recycler {
+                            continue;
+                        }
+                        self.matter[player] -= n * Self::COST;
+                        arrivals[i][player] += n;
+                    }
+                    Order::Build(_) => {}
+                }
+            }
+        }
+
+        let before = self.cells.clone();
+        // fights remove units one for one
+        for (i, arrived) in arrivals.iter().enumerate() {
+            let cell = &mut self.cells[i];
+            let mut units = [0; 2];
+            if let Some(owner) = cell.owner {
+                units[owner] = cell.units;
+            }
+            units[0] += arrived[0];
+            units[1] += arrived[1];
+            let fallen = units[0].min(units[1]);
+            units[0] -= fallen;
+            units[1] -= fallen;
+            cell.units = units[0] + units[1];
+            if units[0] > 0 {
+                cell.owner = Some(0);
+            } else if units[1] > 0 {
+                cell.owner = Some(1);
+            }
+        }
+
+        // each tile loses one scrap however many recyclers reach it, and
+        // every player with a recycler next to it gets the matter
+        let mut recycled = vec![[false; 2]; self.cells.len()];
+        for (i, cell) in self.cells.iter().enumerate() {
+            let Some(owner) = cell.owner.filter(|_| cell.recycler) else {
+                continue;
+            };
+            for n in std::iter::once(i).chain(self.neighbours(i)) {
+                if self.cells[n].scrap > 0 {
+                    recycled[n][owner] = true;
+                }
+            }
+        }
+        for (cell, by) in self.cells.iter_mut().zip(recycled.iter()) {
+            if !by[0] && !by[1] {
+                continue;
+            }
+            cell.scrap -= 1;
+            for (player, &got) in by.iter().enumerate() {
+                if got {
+                    self.matter[player] += 1;
+                }
+            }
+            if cell.scrap == 0 {
+                *cell = Cell::default();
+            }
+        }
+
+        for m in self.matter.iter_mut() {
+            *m += Self::INCOME;
+        }
+        let changed = before
+            .iter()
+            .zip(self.cells.iter())
+            .any(|(a, b)| a.scrap != b.scrap || a.owner != b.owner);
+        self.stale = if changed { 0 } else { self.stale + 1 };
+        self.turn += 1;
+    }
+
+    fn tiles(&self, player: usize) -> usize {
+        self.cells
+            .iter()
+            .filter(|c| c.owner == Some(player))
+            .count()
+    }
+}
+
+impl Referee for FallChallenge2022 {
+    fn initial_input(&self, _player: usize) -> String {
+        format!("{} {}\n", self.width, self.height)
+    }
+
+    fn turn_input(&self, player: usize) -> String {
+        let mut lines = vec![format!(
+            "{} {}",
+            self.matter[player],
+            self.matter[1 - player]
+        )];
+        for (i, cell) in self.cells.iter().enumerate() {
+            let owner = match cell.owner {
+                Some(p) if p == player => 1,
+                Some(_) => 0,
+                None => -1,
+            };
+            let mine = cell.owner == Some(player);
+            let in_range = cell.scrap > 0
+                && std::iter::once(i)
+                    .chain(self.neighbours(i))
+                    .any(|n| self.cells[n].recycler);
+            lines.push(format!(
+                "{} {} {} {} {} {} {}",
+                cell.scrap,
+                owner,
+                cell.units,
+                cell.recycler as i32,
+                (mine && cell.units == 0 && !cell.recycler) as i32,
+                (mine && !cell.recycler) as i32,
+                in_range as i32
+            ));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn expected_lines(&self, _player: usize) -> usize {
+        1
+    }
+
+    fn time_limit(&self, first_turn: bool) -> Duration {
+        Duration::from_millis(if first_turn { 1000 } else { 50 })
+    }
+
+    fn play_turn(&mut self, outputs: [&[String]; 2]) {
+        let mut orders = [Vec::new(), Vec::new()];
+        for (player, lines) in outputs.iter().enumerate() {
+            match lines.first() {
+                Some(line) => orders[player] = self.parse(line),
+                None => self.disqualified[player] = true,
+            }
+        }
+        if self.outcome().is_some() {
+            return;
+        }
+        self.apply(orders);
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match self.disqualified {
+            [true, true] => return Some(Outcome::Draw),
+            [true, false] => return Some(Outcome::Winner(1)),
+            [false, true] => return Some(Outcome::Winner(0)),
+            _ => {}
+        }
+        let tiles = [self.tiles(0), self.tiles(1)];
+        let wiped = tiles.contains(&0);
+        if !wiped && self.turn < Self::MAX_TURNS && self.stale < Self::MAX_STALE {
+            return None;
+        }
+        Some(match tiles[0].cmp(&tiles[1]) {
+            std::cmp::Ordering::Greater => Outcome::Winner(0),
+            std::cmp::Ordering::Less => Outcome::Winner(1),
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        })
+    }
+}
+
+impl fmt::Display for FallChallenge2022 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "turn {} | {}x{}", self.turn, self.width, self.height)?;
+        for player in 0..2 {
+            write!(
+                f,
+                " | p{} tiles {} matter {}",
+                player,
+                self.tiles(player),
+                self.matter[player]
+            )?;
+        }
+        if let Some(outcome) = self.outcome() {
+            write!(f, " | {}", outcome)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Stand-in referees of the multiplayer challenges in the repository, played
+//! by the arena and used by the bots' tests.
+
+mod fall2022;
+mod spring2021;
+mod spring2022;
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::time::Duration;
+
+pub use fall2022::FallChallenge2022;
+pub use spring2021::SpringChallenge2021;
+pub use spring2022::SpringChallenge2022;
+
+pub const NAMES: [&str; 3] = ["spring2021", "spring2022", "fall2022"];
+
+/// Fresh match of the named game
+pub fn referee(name: &str, seed: u64) -> Option<Box<dyn Referee>> {
+    Some(match name {
+        "spring2021" => Box::new(SpringChallenge2021::new(seed)),
+        "spring2022" => Box::new(SpringChallenge2022::new(seed)),
+        "fall2022" => Box::new(FallChallenge2022::new(seed)),
+        _ => return None,
+    })
+}
+
+/// Small xorshift generator, so a seed always plays out the same match
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform value in `[low, high)`
+    pub fn range(&mut self, low: f32, high: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        low + unit * (high - low)
+    }
+
+    /// Uniform integer in `[low, high]`
+    pub fn int(&mut self, low: i32, high: i32) -> i32 {
+        low + (self.next_u64() % (high - low + 1) as u64) as i32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Winner(usize),
+    Draw,
+}
+
+impl Outcome {
+    /// Same result seen with the players swapped
+    pub fn swapped(self) -> Self {
+        match self {
+            Outcome::Winner(p) => Outcome::Winner(1 - p),
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Winner(p) => write!(f, "player {} wins", p),
+            Outcome::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// Rules of one game, played by the arena for two players.
+///
+/// `Display` is used as the one line summary of a finished match.
+pub trait Referee: fmt::Display {
+    /// Lines sent once before the first turn
+    fn initial_input(&self, player: usize) -> String;
+
+    /// Lines sent at the start of every turn the player is asked to play
+    fn turn_input(&self, player: usize) -> String;
+
+    /// Number of lines the player has to answer this turn, 0 when the
+    /// player sits the turn out
+    fn expected_lines(&self, player: usize) -> usize;
+
+    /// Time the player gets to answer, the first turn usually gets more
+    fn time_limit(&self, first_turn: bool) -> Duration;
+
+    /// Applies both players' output, a player answering with fewer lines than
+    /// expected has crashed or timed out and loses
+    fn play_turn(&mut self, outputs: [&[String]; 2]);
+
+    fn outcome(&self) -> Option<Outcome>;
+}
@@ -0,0 +1,420 @@
+//! Spring Challenge 2021 (Photosynthesis) stand-in referee.
+//!
+//! The 37 cells spiral out from the center like on the official board, both
+//! players start with two small trees on the outer ring placed point
+//! symmetrically, and the game lasts 24 days.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::{Outcome, Referee, Rng};
+
+const BOARD_SIZE: usize = 37;
+/// Cube coordinate offsets, indexed like the neighbours of a cell
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, -1, 0),
+    (1, 0, -1),
+    (0, 1, -1),
+    (-1, 1, 0),
+    (-1, 0, 1),
+    (0, -1, 1),
+];
+
+fn add(a: (i32, i32, i32), b: (i32, i32, i32)) -> (i32, i32, i32) {
+    (a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+
+fn distance(a: (i32, i32, i32), b: (i32, i32, i32)) -> i32 {
+    ((a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs()) / 2
+}
+
+/// Cube coordinates of the cells in index order
+fn spiral() -> Vec<(i32, i32, i32)> {
+    let mut coords = vec![(0, 0, 0)];
+    for radius in 1..=3 {
+        let d = DIRECTIONS[0];
+        let mut c = (d.0 * radius, d.1 * radius, d.2 * radius);
+        for i in 0..6 {
+            for _ in 0..radius {
+                coords.push(c);
+                c = add(c, DIRECTIONS[(i + 2) % 6]);
+            }
+        }
+    }
+    coords
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tree {
+    size: usize,
+    owner: usize,
+    dormant: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Player {
+    sun: i32,
+    score: i32,
+    waiting: bool,
+    disqualified: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Wait,
+    Grow(usize),
+    Seed(usize, usize),
+    Complete(usize),
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Order::Wait => write!(f, "WAIT"),
+            Order::Grow(i) => write!(f, "GROW {}", i),
+            Order::Seed(from, to) => write!(f, "SEED {} {}", from, to),
+            Order::Complete(i) => write!(f, "COMPLETE {}", i),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpringChallenge2021 {
+    coords: Vec<(i32, i32, i32)>,
+    neighbours: Vec<[i32; 6]>,
+    richness: Vec<i32>,
+    trees: Vec<Option<Tree>>,
+    players: [Player; 2],
+    day: i32,
+    nutrients: i32,
+}
+
+impl SpringChallenge2021 {
+    const GAME_LENGTH: i32 = 24;
+    const INITIAL_NUTRIENTS: i32 = 20;
+    const GROW_BASE_COST: [i32; 3] = [1, 3, 7];
+    const COMPLETE_COST: i32 = 4;
+    const MAX_UNUSABLE_PAIRS: i32 = 2;
+
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let coords = spiral();
+        let neighbours = coords
+            .iter()
+            .map(|&c| {
+                DIRECTIONS.map(|d| {
+                    let n = add(c, d);
+                    coords.iter().position(|&o| o == n).map_or(-1, |i| i as i32)
+                })
+            })
+            .collect();
+        let richness = coords
+            .iter()
+            .map(|&c| match distance(c, (0, 0, 0)) {
+                0 | 1 => 3,
+                2 => 2,
+                _ => 1,
+            })
+            .collect();
+        let mut game = Self {
+            coords,
+            neighbours,
+            richness,
+            trees: vec![None; BOARD_SIZE],
+            players: Default::default(),
+            day: 0,
+            nutrients: Self::INITIAL_NUTRIENTS,
+        };
+        for _ in 0..rng.int(0, Self::MAX_UNUSABLE_PAIRS) {
+            let i = rng.int(1, BOARD_SIZE as i32 - 1) as usize;
+            let opposite = game.opposite(i);
+            game.richness[i] = 0;
+            game.richness[opposite] = 0;
+        }
+        let mut planted = 0;
+        while planted < 2 {
+            let i = rng.int(19, BOARD_SIZE as i32 - 1) as usize;
+            let opposite = game.opposite(i);
+            if game.richness[i] == 0 || game.trees[i].is_some() || game.trees[opposite].is_some() {
+                continue;
+            }
+            for (owner, cell) in [(0, i), (1, opposite)] {
+                game.trees[cell] = Some(Tree {
+                    size: 1,
+                    owner,
+                    dormant: false,
+                });
+            }
+            planted += 1;
+        }
+        game.gather_sun();
+        game
+    }
+
+    fn opposite(&self, i: usize) -> usize {
+        let c = self.coords[i];
+        self.coords
+            .iter()
+            .position(|&o| o == (-c.0, -c.1, -c.2))
+            .unwrap()
+    }
+
+    fn count(&self, player: usize, size: usize) -> i32 {
+        self.trees
+            .iter()
+            .flatten()
+            .filter(|t| t.owner == player && t.size == size)
+            .count() as i32
+    }
+
+    fn grow_cost(&self, player: usize, size: usize) -> i32 {
+        Self::GROW_BASE_COST[size] + self.count(player, size + 1)
+    }
+
+    fn cost(&self, player: usize, order: &Order) -> i32 {
+        match *order {
+            Order::Wait => 0,
+            Order::Grow(i) => self.trees[i].map_or(0, |t| self.grow_cost(player, t.size)),
+            Order::Seed(..) => self.count(player, 0),
+            Order::Complete(_) => Self::COMPLETE_COST,
+        }
+    }
+
+    /// Legal actions of the player, as listed in its turn input
+    fn possible_orders(&self, player: usize) -> Vec<Order> {
+        let mut orders = vec![Order::Wait];
+        let sun = self.players[player].sun;
+        let seed_cost = self.count(player, 0);
+        for (i, tree) in self.trees.iter().enumerate() {
+            let Some(tree) = tree.filter(|t| t.owner == player && !t.dormant) else {
+                continue;
+            };
+            if tree.size == 3 && sun >= Self::COMPLETE_COST {
+                orders.push(Order::Complete(i));
+            }
+            if tree.size < 3 && sun >= self.grow_cost(player, tree.size) {
+                orders.push(Order::Grow(i));
+            }
+            if tree.size > 0 && sun >= seed_cost {
+                for (j, &c) in self.coords.iter().enumerate() {
+                    if self.richness[j] > 0
+                        && self.trees[j].is_none()
+                        && distance(c, self.coords[i]) <= tree.size as i32
+                    {
+                        orders.push(Order::Seed(i, j));
+                    }
+                }
+            }
+        }
+        orders
+    }
+
+    fn parse(line: &str) -> Option<Order> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            ["WAIT", ..] => Some(Order::Wait),
+            ["GROW", i, ..] => Some(Order::Grow(i.parse().ok()?)),
+            ["SEED", from, to, ..] => Some(Order::Seed(from.parse().ok()?, to.parse().ok()?)),
+            ["COMPLETE", i, ..] => Some(Order::Complete(i.parse().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Trees in the shadow of a tree at least as big, the sun pointing in
+    /// direction `day % 6`
+    fn gather_sun(&mut self) {
+        let dir = (self.day % 6) as usize;
+        let mut shadow = [0; BOARD_SIZE];
+        for (i, tree) in self.trees.iter().enumerate() {
+            let Some(tree) = tree else {
+                continue;
+            };
+            let mut cell = i as i32;
+            for _ in 0..tree.size {
+                cell = self.neighbours[cell as usize][dir];
+                if cell < 0 {
+                    break;
+                }
+                shadow[cell as usize] = shadow[cell as usize].max(tree.size);
+            }
+        }
+        for (i, tree) in self.trees.iter().enumerate() {
+            let Some(tree) = tree else {
+                continue;
+            };
+            if tree.size > shadow[i] {
+                self.players[tree.owner].sun += tree.size as i32;
+            }
+        }
+    }
+
+    /// Applies both actions at once, seeds on the same cell cancel out
+    fn apply(&mut self, orders: [Option<Order>; 2]) {
+        let legal = [0, 1].map(|p| {
+            orders[p].filter(|o| !self.players[p].waiting && self.possible_orders(p).contains(o))
+        });
+        let clash = match legal {
+            [Some(Order::Seed(_, a)), Some(Order::Seed(_, b))] => a == b,
+            _ => false,
+        };
+        let mut completed = 0;
+        for (player, order) in legal.iter().enumerate() {
+            let Some(order) = *order else {
+                continue;
+            };
+            let cost = self.cost(player, &order);
+            match order {
+                Order::Wait => self.players[player].waiting = true,
+                Order::Grow(i) => {
+                    let tree = self.trees[i].as_mut().unwrap();
+                    tree.size += 1;
+                    tree.dormant = true;
+                }
+                Order::Seed(from, to) => {
+                    self.trees[from].as_mut().unwrap().dormant = true;
+                    if clash {
+                        continue;
+                    }
+                    self.trees[to] = Some(Tree {
+                        size: 0,
+                        owner: player,
+                        dormant: true,
+                    });
+                }
+                Order::Complete(i) => {
+                    let bonus = [0, 0, 2, 4][self.richness[i] as usize];
+                    self.players[player].score += self.nutrients + bonus;
+                    self.trees[i] = None;
+                    completed += 1;
+                }
+            }
+            self.players[player].sun -= cost;
+        }
+        self.nutrients = (self.nutrients - completed).max(0);
+
+        if self.players.iter().all(|p| p.waiting) {
+            self.day += 1;
+            if self.day < Self::GAME_LENGTH {
+                for p in self.players.iter_mut() {
+                    p.waiting = false;
+                }
+                for tree in self.trees.iter_mut().flatten() {
+                    tree.dormant = false;
+                }
+                self.gather_sun();
+            }
+        }
+    }
+
+    fn final_score(&self, player: usize) -> (i32, usize) {
+        let p = &self.players[player];
+        let trees = self.trees.iter().flatten().filter(|t| t.owner == player);
+        (p.score + p.sun / 3, trees.count())
+    }
+}
+
+impl Referee for SpringChallenge2021 {
+    fn initial_input(&self, _player: usize) -> String {
+        let mut lines = vec![BOARD_SIZE.to_string()];
+        for (i, n) in self.neighbours.iter().enumerate() {
+            lines.push(format!(
+                "{} {} {} {} {} {} {} {}",
+                i, self.richness[i], n[0], n[1], n[2], n[3], n[4], n[5]
+            ));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn turn_input(&self, player: usize) -> String {
+        let me = &self.players[player];
+        let opp = &self.players[1 - player];
+        let mut lines = vec![
+            self.day.to_string(),
+            self.nutrients.to_string(),
+            format!("{} {}", me.sun, me.score),
+            format!("{} {} {}", opp.sun, opp.score, opp.waiting as i32),
+        ];
+        let trees = self
+            .trees
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| t.map(|t| (i, t)))
+            .collect::<Vec<_>>();
+        lines.push(trees.len().to_string());
+        for (i, t) in trees {
+            lines.push(format!(
+                "{} {} {} {}",
+                i,
+                t.size,
+                (t.owner == player) as i32,
+                t.dormant as i32
+            ));
+        }
+        let orders = self.possible_orders(player);
+        lines.push(orders.len().to_string());
+        lines.extend(orders.iter().map(|o| o.to_string()));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn expected_lines(&self, player: usize) -> usize {
+        if self.players[player].waiting {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn time_limit(&self, first_turn: bool) -> Duration {
+        Duration::from_millis(if first_turn { 1000 } else { 100 })
+    }
+
+    fn play_turn(&mut self, outputs: [&[String]; 2]) {
+        let mut orders = [None, None];
+        for (player, lines) in outputs.iter().enumerate() {
+            if self.players[player].waiting {
+                continue;
+            }
+            match lines.first() {
+                // an unknown command is played as WAIT
+                Some(line) => orders[player] = Some(Self::parse(line).unwrap_or(Order::Wait)),
+                None => self.players[player].disqualified = true,
+            }
+        }
+        if self.outcome().is_some() {
+            return;
+        }
+        self.apply(orders);
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match (self.players[0].disqualified, self.players[1].disqualified) {
+            (true, true) => return Some(Outcome::Draw),
+            (true, false) => return Some(Outcome::Winner(1)),
+            (false, true) => return Some(Outcome::Winner(0)),
+            _ => {}
+        }
+        if self.day < Self::GAME_LENGTH {
+            return None;
+        }
+        Some(match self.final_score(0).cmp(&self.final_score(1)) {
+            std::cmp::Ordering::Greater => Outcome::Winner(0),
+            std::cmp::Ordering::Less => Outcome::Winner(1),
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        })
+    }
+}
+
+impl fmt::Display for SpringChallenge2021 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} | nutrients {}", self.day, self.nutrients)?;
+        for (i, p) in self.players.iter().enumerate() {
+            write!(f, " | p{} score {} sun {}", i, p.score, p.sun)?;
+        }
+        if let Some(outcome) = self.outcome() {
+            write!(f, " | {}", outcome)?;
+        }
+        Ok(())
+    }
+}
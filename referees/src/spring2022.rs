@@ -0,0 +1,549 @@
+//! Spring Challenge 2022 (Spider Attack) stand-in referee.
+//!
+//! Both bases, all six heroes and every monster, producing the exact lines
+//! the spring_challenge_2022 bot reads.
+
+use std::fmt;
+use std::time::Duration;
+
+use cg_common::spring2022::*;
+use cg_common::Vec2f as Vec2;
+
+use crate::{Outcome, Referee, Rng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Monster,
+    Hero(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Entity {
+    id: i32,
+    kind: Kind,
+    pos: Vec2,
+    velocity: Vec2,
+    hp: i32,
+    shield: i32,
+    controlled: bool,
+    control_target: Option<Vec2>,
+    pushed: bool,
+    /// Shielded at the start of the turn, a shield cast during the turn only
+    /// protects from the next one
+    immune: bool,
+}
+
+impl Entity {
+    fn new(id: i32, kind: Kind, pos: Vec2, velocity: Vec2, hp: i32) -> Self {
+        Self {
+            id,
+            kind,
+            pos,
+            velocity,
+            hp,
+            shield: 0,
+            controlled: false,
+            control_target: None,
+            pushed: false,
+            immune: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Side {
+    base: Vec2,
+    hp: i32,
+    mana: i32,
+    wild_mana: i32,
+    disqualified: bool,
+}
+
+/// Single hero command as sent by a bot, the message part is ignored
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Order {
+    Wait,
+    Move(Vec2),
+    Wind(Vec2),
+    Shield(i32),
+    Control(i32, Vec2),
+}
+
+impl Order {
+    fn parse(line: &str) -> Option<Self> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let point = |x: &str, y: &str| -> Option<Vec2> {
+            Some(Vec2 {
+                x: x.parse::<i32>().ok()? as f32,
+                y: y.parse::<i32>().ok()? as f32,
+            })
+        };
+        match words.as_slice() {
+            ["WAIT", ..] => Some(Order::Wait),
+            ["MOVE", x, y, ..] => point(x, y).map(Order::Move),
+            ["SPELL", "WIND", x, y, ..] => point(x, y).map(Order::Wind),
+            ["SPELL", "SHIELD", id, ..] => id.parse().ok().map(Order::Shield),
+            ["SPELL", "CONTROL", id, x, y, ..] => {
+                Some(Order::Control(id.parse().ok()?, point(x, y)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpringChallenge2022 {
+    turn: u32,
+    rng: Rng,
+    sides: [Side; 2],
+    heroes: Vec<Entity>,
+    monsters: Vec<Entity>,
+    next_id: i32,
+}
+
+impl SpringChallenge2022 {
+    const SPAWN_INTERVAL: u32 = 3;
+    const MONSTER_HP: i32 = 10;
+    const HERO_OFFSETS: [Vec2; 3] = [
+        Vec2 {
+            x: 1414.0,
+            y: 849.0,
+        },
+        Vec2 {
+            x: 1131.0,
+            y: 1131.0,
+        },
+        Vec2 {
+            x: 849.0,
+            y: 1414.0,
+        },
+    ];
+
+    pub fn new(seed: u64) -> Self {
+        let sides = [Vec2::ZERO, MAP].map(|base| Side {
+            base,
+            hp: BASE_HP,
+            mana: 0,
+            wild_mana: 0,
+            disqualified: false,
+        });
+        let mut heroes = Vec::with_capacity(6);
+        for (player, side) in sides.iter().enumerate() {
+            for (i, offset) in Self::HERO_OFFSETS.iter().enumerate() {
+                let pos = if player == 0 {
+                    side.base + *offset
+                } else {
+                    side.base - *offset
+                };
+                let id = (player * 3 + i) as i32;
+                heroes.push(Entity::new(id, Kind::Hero(player), pos, Vec2::ZERO, -1));
+            }
+        }
+        Self {
+            turn: 0,
+            rng: Rng::new(seed),
+            sides,
+            heroes,
+            monsters: Vec::new(),
+            next_id: 6,
+        }
+    }
+
+    fn is_visible(&self, player: usize, pos: &Vec2) -> bool {
+        pos.distance(&self.sides[player].base) <= BASE_VIEW_RANGE
+            || self.heroes[player * 3..player * 3 + 3]
+                .iter()
+                .any(|h| h.pos.distance(pos) <= HERO_VIEW_RANGE)
+    }
+
+    fn targeted_base(&self, pos: &Vec2) -> Option<usize> {
+        self.sides
+            .iter()
+            .position(|s| s.base.distance(pos) <= AGGRO_RANGE)
+    }
+
+    /// Base the monster ends up targeting if it keeps its trajectory
+    fn threat(&self, m: &Entity) -> Option<usize> {
+        let mut pos = m.pos;
+        while pos.in_bounds(&MAP) {
+            if let Some(b) = self.targeted_base(&pos) {
+                return Some(b);
+            }
+            if m.velocity == Vec2::ZERO {
+                break;
+            }
+            pos += m.velocity;
+        }
+        None
+    }
+
+    fn entity_mut(&mut self, id: i32) -> Option<&mut Entity> {
+        self.heroes
+            .iter_mut()
+            .chain(self.monsters.iter_mut())
+            .find(|e| e.id == id)
+    }
+
+    /// Spends the mana if the player can afford a spell
+    fn pay_spell(&mut self, player: usize) -> bool {
+        if self.sides[player].mana < SPELL_COST {
+            return false;
+        }
+        self.sides[player].mana -= SPELL_COST;
+        true
+    }
+
+    fn cast_controls(&mut self, orders: &[Order; 6]) {
+        for (i, order) in orders.iter().enumerate() {
+            let Order::Control(id, t) = *order else {
+                continue;
+            };
+            let player = i / 3;
+            let caster = self.heroes[i].pos;
+            let in_range = self.entity_mut(id).is_some_and(|e| {
+                e.kind != Kind::Hero(player) && e.pos.distance(&caster) <= CONTROL_RANGE
+            });
+            if !in_range || !self.pay_spell(player) {
+                continue;
+            }
+            let e = self.entity_mut(id).unwrap();
+            if !e.immune {
+                e.controlled = true;
+                e.control_target = Some(t);
+            }
+        }
+    }
+
+    fn cast_shields(&mut self, orders: &[Order; 6]) {
+        for (i, order) in orders.iter().enumerate() {
+            let Order::Shield(id) = *order else {
+                continue;
+            };
+            let player = i / 3;
+            let caster = self.heroes[i].pos;
+            let in_range = self
+                .entity_mut(id)
+                .is_some_and(|e| e.pos.distance(&caster) <= SHIELD_RANGE);
+            if !in_range || !self.pay_spell(player) {
+                continue;
+            }
+            let e = self.entity_mut(id).unwrap();
+            if e.shield == 0 {
+                e.shield = SHIELD_DURATION;
+            }
+        }
+    }
+
+    /// Pays for the winds, which only blow once the heroes have moved and
+    /// fought
+    fn cast_winds(&mut self, orders: &[Order; 6]) -> Vec<(usize, Vec2)> {
+        let mut winds = Vec::new();
+        for (i, order) in orders.iter().enumerate() {
+            let Order::Wind(t) = *order else {
+                continue;
+            };
+            if t != self.heroes[i].pos && self.pay_spell(i / 3) {
+                winds.push((i, t));
+            }
+        }
+        winds
+    }
+
+    fn blow_winds(&mut self, winds: &[(usize, Vec2)]) {
+        let mut pushes = Vec::new();
+        for &(i, t) in winds {
+            let player = i / 3;
+            let caster = self.heroes[i].pos;
+            let push = unit(t - caster) * WIND_PUSH;
+            for e in self.heroes.iter().chain(self.monsters.iter()) {
+                if e.kind != Kind::Hero(player)
+                    && !e.immune
+                    && e.pos.distance(&caster) <= WIND_RANGE
+                {
+                    pushes.push((e.id, push));
+                }
+            }
+        }
+        // winds are simultaneous, so collect first and push after
+        for (id, push) in pushes {
+            let e = self.entity_mut(id).unwrap();
+            e.pos = round(e.pos + push);
+            e.pushed = true;
+            if let Kind::Hero(_) = e.kind {
+                e.pos = clamp(e.pos);
+            }
+        }
+    }
+
+    fn move_heroes(&mut self, orders: &[Order; 6]) {
+        for (hero, order) in self.heroes.iter_mut().zip(orders.iter()) {
+            let Order::Move(t) = *order else {
+                continue;
+            };
+            hero.pos = if hero.pos.distance(&t) <= HERO_SPEED {
+                t
+            } else {
+                round(hero.pos + (t - hero.pos).normalize() * HERO_SPEED)
+            };
+            hero.pos = clamp(hero.pos);
+        }
+    }
+
+    fn fight(&mut self) {
+        for hero in self.heroes.iter() {
+            let Kind::Hero(owner) = hero.kind else {
+                continue;
+            };
+            for m in self.monsters.iter_mut() {
+                if m.hp <= 0 || m.pos.distance(&hero.pos) > ATTACK_RANGE {
+                    continue;
+                }
+                m.hp -= HERO_DAMAGE;
+                self.sides[owner].mana += HERO_DAMAGE;
+                if m.pos.distance(&self.sides[owner].base) > AGGRO_RANGE {
+                    self.sides[owner].wild_mana += HERO_DAMAGE;
+                }
+            }
+        }
+        self.monsters.retain(|m| m.hp > 0);
+    }
+
+    fn move_monsters(&mut self) {
+        let bases = [self.sides[0].base, self.sides[1].base];
+        for m in self.monsters.iter_mut() {
+            if let Some(t) = m.control_target.take() {
+                m.velocity = round(unit(t - m.pos) * MONSTER_SPEED);
+            } else if let Some(b) = bases.iter().find(|b| b.distance(&m.pos) <= AGGRO_RANGE) {
+                m.velocity = round(unit(*b - m.pos) * MONSTER_SPEED);
+            }
+            if !m.pushed {
+                m.pos += m.velocity;
+            }
+        }
+        let sides = &mut self.sides;
+        self.monsters.retain(|m| {
+            for side in sides.iter_mut() {
+                if side.base.distance(&m.pos) <= BASE_DAMAGE_RANGE {
+                    side.hp = (side.hp - 1).max(0);
+                    return false;
+                }
+            }
+            m.pos.in_bounds(&MAP)
+        });
+    }
+
+    /// Monsters come in symmetric pairs from the top and bottom edges
+    fn spawn_monsters(&mut self) {
+        let hp = Self::MONSTER_HP + (self.turn / 20) as i32;
+        let x = self.rng.range(MAP.x * 0.25, MAP.x * 0.75);
+        let angle = self
+            .rng
+            .range(30.0_f32.to_radians(), 150.0_f32.to_radians());
+        let pos = round(Vec2 { x, y: 0.0 });
+        let velocity = round(
+            Vec2 {
+                x: angle.cos(),
+                y: angle.sin(),
+            } * MONSTER_SPEED,
+        );
+        self.monsters
+            .push(Entity::new(self.next_id, Kind::Monster, pos, velocity, hp));
+        self.monsters.push(Entity::new(
+            self.next_id + 1,
+            Kind::Monster,
+            MAP - pos,
+            Vec2::ZERO - velocity,
+            hp,
+        ));
+        self.next_id += 2;
+    }
+}
+
+impl Referee for SpringChallenge2022 {
+    fn initial_input(&self, player: usize) -> String {
+        format!("{}\n3\n", self.sides[player].base)
+    }
+
+    fn turn_input(&self, player: usize) -> String {
+        let me = &self.sides[player];
+        let enemy = &self.sides[1 - player];
+        let mut lines = vec![
+            format!("{} {}", me.hp, me.mana),
+            format!("{} {}", enemy.hp, enemy.mana),
+        ];
+        let mut entities = Vec::new();
+        for hero in self.heroes.iter() {
+            let Kind::Hero(owner) = hero.kind else {
+                continue;
+            };
+            if owner != player && !self.is_visible(player, &hero.pos) {
+                continue;
+            }
+            let tp = if owner == player { 1 } else { 2 };
+            entities.push(format!(
+                "{} {} {} {} {} -1 -1 -1 -1 -1",
+                hero.id, tp, hero.pos, hero.shield, hero.controlled as i32
+            ));
+        }
+        for m in self.monsters.iter() {
+            if !self.is_visible(player, &m.pos) {
+                continue;
+            }
+            let near_base = self.targeted_base(&m.pos).is_some();
+            let threat_for = match self.threat(m) {
+                Some(b) if b == player => 1,
+                Some(_) => 2,
+                None => 0,
+            };
+            entities.push(format!(
+                "{} 0 {} {} {} {} {} {} {}",
+                m.id,
+                m.pos,
+                m.shield,
+                m.controlled as i32,
+                m.hp,
+                m.velocity,
+                near_base as i32,
+                threat_for
+            ));
+        }
+        lines.push(entities.len().to_string());
+        lines.append(&mut entities);
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    fn expected_lines(&self, _player: usize) -> usize {
+        3
+    }
+
+    fn time_limit(&self, first_turn: bool) -> Duration {
+        Duration::from_millis(if first_turn { 1000 } else { 50 })
+    }
+
+    fn play_turn(&mut self, outputs: [&[String]; 2]) {
+        let mut orders = [Order::Wait; 6];
+        for (player, lines) in outputs.iter().enumerate() {
+            for i in 0..3 {
+                match lines.get(i).and_then(|l| Order::parse(l)) {
+                    Some(order) => orders[player * 3 + i] = order,
+                    None => self.sides[player].disqualified = true,
+                }
+            }
+        }
+        if self.outcome().is_some() {
+            return;
+        }
+
+        // heroes controlled last turn have their order replaced
+        for (i, hero) in self.heroes.iter_mut().enumerate() {
+            if let Some(t) = hero.control_target.take() {
+                orders[i] = Order::Move(t);
+            }
+        }
+        for e in self.heroes.iter_mut().chain(self.monsters.iter_mut()) {
+            e.controlled = false;
+            e.pushed = false;
+            e.immune = e.shield > 0;
+        }
+
+        // same order as the official referee
+        self.cast_controls(&orders);
+        self.cast_shields(&orders);
+        let winds = self.cast_winds(&orders);
+        self.move_heroes(&orders);
+        self.fight();
+        self.blow_winds(&winds);
+        self.move_monsters();
+
+        for e in self.heroes.iter_mut().chain(self.monsters.iter_mut()) {
+            e.shield = (e.shield - 1).max(0);
+        }
+        self.turn += 1;
+        if self.turn.is_multiple_of(Self::SPAWN_INTERVAL) {
+            self.spawn_monsters();
+        }
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        match (self.sides[0].disqualified, self.sides[1].disqualified) {
+            (true, true) => return Some(Outcome::Draw),
+            (true, false) => return Some(Outcome::Winner(1)),
+            (false, true) => return Some(Outcome::Winner(0)),
+            _ => {}
+        }
+        let dead = self.sides.iter().any(|s| s.hp <= 0);
+        if !dead && self.turn < MAX_TURNS {
+            return None;
+        }
+        let (a, b) = (&self.sides[0], &self.sides[1]);
+        Some(match a.hp.cmp(&b.hp).then(a.wild_mana.cmp(&b.wild_mana)) {
+            std::cmp::Ordering::Greater => Outcome::Winner(0),
+            std::cmp::Ordering::Less => Outcome::Winner(1),
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        })
+    }
+}
+
+impl fmt::Display for SpringChallenge2022 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "turn {}", self.turn)?;
+        for (i, side) in self.sides.iter().enumerate() {
+            write!(
+                f,
+                " | p{} hp {} mana {} wild {}",
+                i, side.hp, side.mana, side.wild_mana
+            )?;
+        }
+        if let Some(outcome) = self.outcome() {
+            write!(f, " | {}", outcome)?;
+        }
+        Ok(())
+    }
+}
+
+fn round(v: Vec2) -> Vec2 {
+    Vec2 {
+        x: v.x.round(),
+        y: v.y.round(),
+    }
+}
+
+fn clamp(v: Vec2) -> Vec2 {
+    Vec2 {
+        x: v.x.clamp(0.0, MAP.x),
+        y: v.y.clamp(0.0, MAP.y),
+    }
+}
+
+/// Direction of `v`, none for a monster already on its target
+fn unit(v: Vec2) -> Vec2 {
+    if v == Vec2::ZERO {
+        v
+    } else {
+        v.normalize()
+    }
+}
+
+#[cfg(test)]
+impl SpringChallenge2022 {
+    /// Moves a hero or a monster to `pos`, an unknown id adds a still monster
+    pub(crate) fn place(&mut self, id: i32, pos: Vec2) {
+        match self.entity_mut(id) {
+            Some(e) => e.pos = pos,
+            None => self.monsters.push(Entity::new(
+                id,
+                Kind::Monster,
+                pos,
+                Vec2::ZERO,
+                Self::MONSTER_HP,
+            )),
+        }
+    }
+
+    pub(crate) fn set_mana(&mut self, mana: i32) {
+        for side in self.sides.iter_mut() {
+            side.mana = mana;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use cg_common::Vec2f;
+
+use crate::*;
+
+fn play_out(referee: &mut dyn Referee, answer: [&str; 2]) -> Outcome {
+    for _ in 0..1000 {
+        if let Some(outcome) = referee.outcome() {
+            return outcome;
+        }
+        let outputs = [0, 1].map(|p| vec![answer[p].to_string(); referee.expected_lines(p)]);
+        referee.play_turn([&outputs[0], &outputs[1]]);
+    }
+    panic!("game did not end");
+}
+
+#[test]
+fn rng_is_deterministic() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    for _ in 0..100 {
+        let x = a.int(3, 7);
+        assert_eq!(x, b.int(3, 7));
+        assert!((3..=7).contains(&x));
+    }
+}
+
+#[test]
+fn spring2022_input_and_draw() {
+    let mut referee = SpringChallenge2022::new(1);
+    assert_eq!(referee.initial_input(1), "17630 9000\n3\n");
+    let input = referee.turn_input(0);
+    assert!(input.starts_with("3 0\n3 0\n3\n0 1 1414 849 0 0"));
+    // only own heroes are visible on the first turn
+    assert_eq!(input.lines().count(), 6);
+    for line in input.lines().skip(3) {
+        assert_eq!(line.split(' ').count(), 11, "{}", line);
+    }
+    // nobody defends, both bases fall at the same time
+    assert_eq!(play_out(&mut referee, ["WAIT", "WAIT"]), Outcome::Draw);
+}
+
+#[test]
+fn fall2022_is_symmetric() {
+    let referee = FallChallenge2022::new(3);
+    let (w, h) = {
+        let dims = referee.initial_input(0);
+        let mut it = dims.split_whitespace().map(|v| v.parse::<usize>().unwrap());
+        (it.next().unwrap(), it.next().unwrap())
+    };
+    let input = [referee.turn_input(0), referee.turn_input(1)];
+    let cells = input.map(|i| i.lines().skip(1).map(String::from).collect::<Vec<_>>());
+    assert_eq!(cells[0].len(), w * h);
+    for y in 0..h {
+        for x in 0..w {
+            assert_eq!(cells[0][y * w + x], cells[1][y * w + w - 1 - x]);
+        }
+    }
+    // the start is a plus of owned tiles with a robot on each arm
+    assert_eq!(
+        cells[0]
+            .iter()
+            .filter(|c| c.contains(" 1 1 0 0 1 0"))
+            .count(),
+        4
+    );
+}
+
+#[test]
+fn fall2022_spawn_and_stall() {
+    let mut referee = FallChallenge2022::new(3);
+    let w = referee
+        .initial_input(0)
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .parse::<usize>()
+        .unwrap();
+    let input = referee.turn_input(0);
+    // the empty center of the plus
+    let center = input
+        .lines()
+        .skip(1)
+        .position(|c| c.ends_with(" 1 0 0 1 1 0"))
+        .unwrap();
+    let outputs = [
+        vec![format!(
+            "SPAWN 1 {} {};SPAWN 1 {} {}",
+            center % w,
+            center / w,
+            center % w,
+            center / w
+        )],
+        vec!["WAIT".to_string()],
+    ];
+    referee.play_turn([&outputs[0], &outputs[1]]);
+    let input = referee.turn_input(0);
+    // only one robot was affordable
+    assert!(input.starts_with("10 20\n"));
+    assert!(input
+        .lines()
+        .nth(center + 1)
+        .unwrap()
+        .contains(" 1 1 0 0 1 0"));
+    // nothing changes anymore
+    assert_eq!(play_out(&mut referee, ["WAIT", "WAIT"]), Outcome::Draw);
+    assert!(referee.to_string().starts_with("turn 20 "), "{}", referee);
+}
+
+#[test]
+fn spring2021_board_and_actions() {
+    let mut referee = SpringChallenge2021::new(5);
+    let init = referee.initial_input(0);
+    let lines = init.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "37");
+    assert_eq!(lines[1], "0 3 1 2 3 4 5 6");
+    assert_eq!(lines[2], "1 3 7 8 2 0 6 18");
+    let input = referee.turn_input(0);
+    let lines = input.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "0");
+    assert_eq!(lines[1], "20");
+    assert_eq!(lines[2], "2 0");
+    assert_eq!(lines[4], "4");
+    // the first seed is free, growing is not affordable yet
+    assert!(!lines.iter().any(|l| l.starts_with("GROW")));
+    assert!(lines.iter().any(|l| l.starts_with("SEED")));
+    // waiting all game, the sun decides
+    let outcome = play_out(&mut referee, ["WAIT", "WAIT"]);
+    assert!(referee.to_string().starts_with("day 24 | nutrients 20"));
+    assert_eq!(outcome, Outcome::Draw);
+}
+
+#[test]
+fn missing_output_loses() {
+    let mut referee = SpringChallenge2021::new(5);
+    let outputs = [vec!["WAIT".to_string()], Vec::new()];
+    referee.play_turn([&outputs[0], &outputs[1]]);
+    assert_eq!(referee.outcome(), Some(Outcome::Winner(0)));
+}
+
+#[test]
+fn spring2022_invalid_output_loses() {
+    let mut referee = SpringChallenge2022::new(0);
+    let wait = vec!["WAIT".to_string(); 3];
+    let bad = vec!["MOVE 1".to_string(), "WAIT".to_string()];
+    referee.play_turn([&wait, &bad]);
+    assert_eq!(referee.outcome(), Some(Outcome::Winner(0)));
+}
+
+#[test]
+fn spring2022_wind_blows_after_moves_and_shields() {
+    let mut referee = SpringChallenge2022::new(0);
+    referee.set_mana(20);
+    referee.place(0, Vec2f::new(5000.0, 5000.0));
+    referee.place(1, Vec2f::new(7700.0, 5000.0));
+    referee.place(3, Vec2f::new(6000.0, 5000.0));
+    referee.place(100, Vec2f::new(5500.0, 5000.0));
+    let outputs = [
+        ["SPELL SHIELD 100", "MOVE 6900 5000", "WAIT"],
+        ["SPELL WIND 0 5000", "WAIT", "WAIT"],
+    ]
+    .map(|lines| lines.map(String::from).to_vec());
+    referee.play_turn([&outputs[0], &outputs[1]]);
+    let input = referee.turn_input(0);
+    let line = |id: &str| input.lines().find(|l| l.starts_with(id)).unwrap();
+    // the shield cast this turn does not protect from the wind yet, the
+    // monster was hit by both heroes before being pushed
+    assert!(line("100 0 ").starts_with("100 0 3300 5000 11 0 6 "));
+    // our hero walked into the wind before it blew
+    assert!(line("1 1 ").starts_with("1 1 4700 5000 0 0 "));
+    // and next turn the shield holds
+    let outputs = [
+        ["WAIT", "WAIT", "WAIT"],
+        ["SPELL WIND 0 5000", "WAIT", "WAIT"],
+    ]
+    .map(|lines| lines.map(String::from).to_vec());
+    referee.place(3, Vec2f::new(3800.0, 5000.0));
+    referee.play_turn([&outputs[0], &outputs[1]]);
+    assert!(referee.turn_input(0).contains("\n100 0 3300 5000 10 "));
+}
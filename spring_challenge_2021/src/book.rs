@@ -0,0 +1,351 @@
+//! Opening book, our actions of the first days keyed on the starting
+//! layout: the cells of our two trees and the unusable cells, the
+//! opponent's trees and the unusable cells being symmetric.
+//!
+//! A line reads `<our trees> / <unusable cells> / <day 0> / <day 1> ...`,
+//! the actions of a day separated by `,` and `-` for an empty list. Every
+//! day ends with a WAIT that isn't written. `spring_challenge_2021 book
+//! <replay>` prints the line of a recorded game played by a wider search.
+//!
+//! The lines below are written by `spring_challenge_2021 book` for every
+//! start the server deals, on a board without unusable cells. A layout with
+//! unusable cells plays the line of its trees when it has none of its own,
+//! until one of the actions can't be played.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use cg_common::{ErrorKind, FromLine, ParseError, Words};
+
+use crate::hex::{Layout, BOARD_RADIUS};
+use crate::search::Beam;
+use crate::simulator::State;
+use crate::{Action, ActionKind, Board, Cell, Tree};
+
+/// Days covered by the book
+pub const BOOK_DAYS: i32 = 6;
+
+/// One line per starting layout
+const BOOK: &str = "\
+19 20 / - / - / SEED 19 36 / GROW 20, GROW 36 / GROW 36, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 36 17
+19 21 / - / - / SEED 19 7 / GROW 21, GROW 7 / GROW 7, SEED 21 2 / GROW 2, SEED 7 6 / GROW 2, GROW 6, SEED 7 35
+19 22 / - / - / GROW 19 / GROW 22, SEED 19 8 / GROW 8, SEED 19 18 / GROW 8, GROW 18, SEED 22 10 / GROW 18, GROW 10, SEED 8 0
+19 23 / - / - / GROW 19 / GROW 23, SEED 19 18 / GROW 18, SEED 23 11 / GROW 18, GROW 11, SEED 23 2 / GROW 11, GROW 2, SEED 18 34
+19 24 / - / - / GROW 19 / GROW 24, SEED 19 8 / GROW 8, SEED 19 18 / GROW 8, GROW 18, SEED 24 26 / GROW 18, GROW 26, SEED 8 0
+19 25 / - / - / GROW 19 / GROW 25, SEED 19 8 / GROW 8, SEED 19 18 / GROW 8, GROW 18, SEED 25 10 / GROW 18, GROW 10, SEED 8 0
+19 26 / - / - / GROW 19 / GROW 26, SEED 19 8 / GROW 8, SEED 26 24 / GROW 8, GROW 24, SEED 26 3 / GROW 24, GROW 3, SEED 8 6
+19 27 / - / - / - / GROW 19 / GROW 27, SEED 19 8 / GROW 8, SEED 27 4 / GROW 8, GROW 4, SEED 27 11
+19 29 / - / - / GROW 19 / GROW 29, SEED 19 18 / GROW 18, SEED 29 4 / GROW 18, GROW 4, SEED 29 15 / GROW 4, GROW 15, SEED 18 34
+19 30 / - / - / GROW 19 / GROW 30, SEED 19 18 / GROW 18, SEED 30 32 / GROW 18, GROW 32, SEED 30 4 / GROW 32, GROW 4, SEED 18 34
+19 31 / - / - / GROW 19 / GROW 31, SEED 19 8 / GROW 8, SEED 19 18 / GROW 8, GROW 18, SEED 31 14 / GROW 18, GROW 14, SEED 8 0
+19 32 / - / - / GROW 19 / GROW 32, SEED 19 8 / GROW 8, SEED 19 18 / GROW 8, GROW 18, SEED 32 5 / GROW 5, GROW 8, SEED 32 30
+19 33 / - / - / GROW 19 / GROW 33, SEED 19 8 / GROW 8, SEED 33 15 / GROW 8, GROW 15, SEED 33 6 / GROW 15, GROW 6, SEED 8 22
+19 34 / - / - / GROW 19 / GROW 34, SEED 19 8 / GROW 8, SEED 19 18 / GROW 8, GROW 18, SEED 34 16 / GROW 18, GROW 16, SEED 8 0
+19 35 / - / - / SEED 35 18 / GROW 19, GROW 18 / GROW 18, SEED 19 8 / GROW 8, SEED 18 0 / GROW 8, GROW 0, SEED 18 16
+19 36 / - / - / SEED 36 18 / GROW 36, GROW 18, SEED 19 20 / GROW 20 / GROW 19 / GROW 36, SEED 18 6
+20 21 / - / - / SEED 20 7 / GROW 21, GROW 7 / GROW 7, SEED 21 2 / GROW 2, SEED 7 6 / GROW 2, GROW 6, SEED 7 35
+20 22 / - / - / SEED 20 8 / GROW 22, GROW 8 / GROW 8, SEED 22 10 / GROW 10, SEED 8 0 / GROW 10, GROW 0, SEED 8 18
+20 23 / - / - / GROW 20 / GROW 23, SEED 20 1 / GROW 1, SEED 20 36 / GROW 1, GROW 36, SEED 23 11 / GROW 36, GROW 11, SEED 1 5
+20 24 / - / - / GROW 20 / GROW 24, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 24 3 / GROW 9, GROW 3, SEED 1 5
+20 25 / - / - / GROW 20 / GROW 25, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 20 36 / GROW 9, GROW 36, SEED 1 5
+20 26 / - / - / GROW 20 / GROW 26, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 26 3 / GROW 1, GROW 3, SEED 20 36
+20 27 / - / - / GROW 20 / GROW 27, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 27 11 / GROW 1, GROW 11, SEED 27 4
+20 28 / - / - / GROW 20, SEED 28 27 / GROW 27 / GROW 28, SEED 20 1 / GROW 27, GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 27 11
+20 30 / - / - / GROW 20 / GROW 30, SEED 20 1 / GROW 1, SEED 20 36 / GROW 1, GROW 36, SEED 30 5 / GROW 36, GROW 5, SEED 1 3
+20 31 / - / - / GROW 20 / GROW 31, SEED 20 1 / GROW 1, SEED 20 36 / GROW 1, GROW 36, SEED 31 5 / GROW 36, GROW 5, SEED 1 3
+20 32 / - / - / GROW 20 / GROW 32, SEED 20 1 / GROW 1, SEED 20 36 / GROW 1, GROW 36, SEED 32 5 / GROW 36, GROW 5, SEED 1 3
+20 33 / - / - / GROW 20 / GROW 33, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 20 36 / GROW 1, GROW 36, SEED 33 15
+20 34 / - / - / GROW 20 / GROW 34, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 20 36 / GROW 9, GROW 36, SEED 1 3
+20 35 / - / - / GROW 20 / GROW 35, SEED 20 9 / GROW 9, SEED 35 6 / GROW 9, GROW 6, SEED 35 33 / GROW 6, GROW 33, SEED 9 24
+20 36 / - / - / GROW 20 / GROW 36, SEED 20 1 / GROW 1, SEED 20 9 / GROW 1, GROW 9, SEED 36 17 / GROW 9, GROW 17, SEED 1 3
+21 22 / - / - / SEED 21 8 / GROW 22, GROW 8 / GROW 8, SEED 22 10 / GROW 10, SEED 8 0 / GROW 10, GROW 0, SEED 8 18
+21 23 / - / - / GROW 21 / GROW 23, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 23 11 / GROW 7, GROW 11, SEED 2 4
+21 24 / - / - / GROW 21 / GROW 24, SEED 21 7 / GROW 7, SEED 24 3 / GROW 7, GROW 3, SEED 24 26 / GROW 3, GROW 26, SEED 7 35
+21 25 / - / - / GROW 21 / GROW 25, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 21 23 / GROW 7, GROW 23, SEED 2 4
+21 26 / - / - / GROW 21 / GROW 26, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 21 23 / GROW 2, GROW 23, SEED 26 13
+21 27 / - / - / GROW 21 / GROW 27, SEED 21 2 / GROW 2, SEED 21 23 / GROW 2, GROW 23, SEED 27 4 / GROW 23, GROW 4, SEED 2 6
+21 28 / - / - / GROW 21 / GROW 28, SEED 21 2 / GROW 2, SEED 21 23 / GROW 2, GROW 23, SEED 28 4 / GROW 23, GROW 4, SEED 2 6
+21 29 / - / - / GROW 21, SEED 29 13 / GROW 13 / GROW 29, SEED 21 2 / GROW 13, GROW 2, SEED 21 23 / GROW 2, GROW 23, SEED 13 26
+21 31 / - / - / GROW 21 / GROW 31, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 31 16 / GROW 7, GROW 16, SEED 2 4
+21 32 / - / - / GROW 21 / GROW 32, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 32 17 / GROW 2, GROW 17, SEED 32 5
+21 33 / - / - / GROW 21 / GROW 33, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 33 6 / GROW 2, GROW 6, SEED 21 23
+21 34 / - / - / GROW 21 / GROW 34, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 21 23 / GROW 7, GROW 23, SEED 2 4
+21 35 / - / - / GROW 21 / GROW 35, SEED 21 2 / GROW 2, SEED 21 7 / GROW 2, GROW 7, SEED 35 6 / GROW 7, GROW 6, SEED 2 3
+21 36 / - / - / GROW 21 / GROW 36, SEED 21 2 / GROW 2, SEED 21 23 / GROW 2, GROW 23, SEED 36 17 / GROW 23, GROW 17, SEED 2 4
+22 23 / - / - / SEED 23 24 / GROW 23, GROW 24, SEED 22 21 / GROW 21 / GROW 21, SEED 23 2 / GROW 23
+22 24 / - / - / SEED 24 25 / GROW 24, GROW 25, SEED 22 23 / GROW 23, SEED 24 3 / GROW 3, SEED 24 2 / GROW 3
+22 25 / - / - / GROW 22 / GROW 25, SEED 22 8 / GROW 8, SEED 22 10 / GROW 8, GROW 10, SEED 25 12 / GROW 10, GROW 12, SEED 8 0
+22 26 / - / - / GROW 22 / GROW 26, SEED 22 8 / GROW 8, SEED 26 13 / GROW 8, GROW 13, SEED 26 3 / GROW 13, GROW 3, SEED 8 19
+22 27 / - / - / GROW 22 / GROW 27, SEED 22 8 / GROW 8, SEED 22 10 / GROW 8, GROW 10, SEED 27 4 / GROW 4, GROW 8, SEED 27 29
+22 28 / - / - / GROW 22 / GROW 28, SEED 22 8 / GROW 8, SEED 22 10 / GROW 8, GROW 10, SEED 28 14 / GROW 10, GROW 14, SEED 8 0
+22 29 / - / - / GROW 22 / GROW 29, SEED 22 10 / GROW 10, SEED 29 27 / GROW 10, GROW 27, SEED 29 4 / GROW 27, GROW 4, SEED 10 1
+22 30 / - / - / GROW 22, SEED 30 14 / GROW 14 / GROW 30, SEED 22 10 / GROW 14, GROW 10, SEED 30 5 / GROW 10, GROW 5, SEED 14 0
+22 32 / - / - / - / GROW 22 / GROW 32, SEED 22 8 / GROW 8, SEED 32 5 / GROW 8, GROW 5, SEED 32 17
+22 33 / - / - / GROW 22 / GROW 33, SEED 22 8 / GROW 8, SEED 33 35 / GROW 8, GROW 35, SEED 33 5 / GROW 35, GROW 5, SEED 8 3
+22 34 / - / - / GROW 22 / GROW 34, SEED 22 8 / GROW 8, SEED 22 10 / GROW 8, GROW 10, SEED 34 18 / GROW 10, GROW 18, SEED 8 0
+22 35 / - / - / GROW 22 / GROW 35, SEED 22 8 / GROW 8, SEED 22 10 / GROW 8, GROW 10, SEED 35 33 / GROW 10, GROW 33, SEED 8 0
+22 36 / - / - / GROW 22 / GROW 36, SEED 22 10 / GROW 10, SEED 36 17 / GROW 10, GROW 17, SEED 36 1 / GROW 17, GROW 1, SEED 10 25
+23 24 / - / - / SEED 24 25 / GROW 24, GROW 25, SEED 23 22 / GROW 22, SEED 24 3 / GROW 3 / GROW 3, SEED 24 2
+23 25 / - / - / SEED 23 22 / GROW 25, GROW 22 / GROW 22, SEED 25 12 / GROW 12, SEED 22 8 / GROW 12, GROW 8, SEED 22 20
+23 26 / - / - / GROW 23 / GROW 26, SEED 23 2 / GROW 2, SEED 23 21 / GROW 2, GROW 21, SEED 26 13 / GROW 21, GROW 13, SEED 2 6
+23 27 / - / - / GROW 23 / GROW 27, SEED 23 2 / GROW 2, SEED 23 11 / GROW 2, GROW 11, SEED 27 4 / GROW 11, GROW 4, SEED 2 1
+23 28 / - / - / GROW 23 / GROW 28, SEED 23 2 / GROW 2, SEED 23 11 / GROW 2, GROW 11, SEED 23 21 / GROW 11, GROW 21, SEED 2 6
+23 29 / - / - / GROW 23 / GROW 29, SEED 23 2 / GROW 2, SEED 23 11 / GROW 2, GROW 11, SEED 29 4 / GROW 2, GROW 4, SEED 23 21
+23 30 / - / - / GROW 23 / GROW 30, SEED 23 2 / GROW 2, SEED 23 11 / GROW 2, GROW 11, SEED 30 13 / GROW 2, GROW 13, SEED 30 5
+23 31 / - / - / GROW 23 / GROW 31, SEED 23 2 / GROW 2, SEED 23 11 / GROW 2, GROW 11, SEED 31 14 / GROW 11, GROW 14, SEED 2 6
+23 33 / - / - / - / GROW 23 / GROW 33, SEED 23 2 / GROW 2, SEED 33 6 / GROW 2, GROW 6, SEED 23 21
+23 34 / - / - / GROW 23 / GROW 34, SEED 23 2 / GROW 2, SEED 23 21 / GROW 2, GROW 21, SEED 34 6 / GROW 21, GROW 6, SEED 2 4
+23 35 / - / - / GROW 23 / GROW 35, SEED 23 2 / GROW 2, SEED 23 21 / GROW 2, GROW 21, SEED 35 6 / GROW 21, GROW 6, SEED 2 4
+23 36 / - / - / GROW 23 / GROW 36, SEED 23 2 / GROW 2, SEED 23 11 / GROW 2, GROW 11, SEED 23 21 / GROW 2, GROW 21, SEED 36 17
+24 25 / - / - / SEED 24 23 / GROW 24, GROW 23, SEED 25 26 / GROW 26 / GROW 26, SEED 24 3 / GROW 3, SEED 26 13
+24 26 / - / - / GROW 24 / GROW 26, SEED 24 3 / GROW 3, SEED 24 9 / GROW 3, GROW 9, SEED 26 13 / GROW 9, GROW 13, SEED 3 1
+24 27 / - / - / GROW 24 / GROW 27, SEED 24 9 / GROW 9, SEED 27 4 / GROW 9, GROW 4, SEED 27 29 / GROW 4, GROW 29, SEED 9 20
+24 28 / - / - / GROW 24 / GROW 28, SEED 24 3 / GROW 3, SEED 24 9 / GROW 3, GROW 9, SEED 24 26 / GROW 9, GROW 26, SEED 3 1
+24 29 / - / - / GROW 24 / GROW 29, SEED 24 3 / GROW 3, SEED 24 9 / GROW 3, GROW 9, SEED 24 26 / GROW 3, GROW 26, SEED 29 15
+24 30 / - / - / GROW 24 / GROW 30, SEED 24 3 / GROW 3, SEED 24 26 / GROW 3, GROW 26, SEED 30 5 / GROW 26, GROW 5, SEED 3 1
+24 31 / - / - / GROW 24 / GROW 31, SEED 24 3 / GROW 3, SEED 24 26 / GROW 3, GROW 26, SEED 31 5 / GROW 26, GROW 5, SEED 3 1
+24 32 / - / - / GROW 24 / GROW 32, SEED 24 3 / GROW 3, SEED 24 26 / GROW 3, GROW 26, SEED 32 5 / GROW 26, GROW 5, SEED 3 1
+24 34 / - / - / - / GROW 24 / GROW 34, SEED 24 3 / GROW 3, SEED 24 9 / GROW 3, GROW 9, SEED 34 18
+24 35 / - / - / GROW 24 / GROW 35, SEED 24 3 / GROW 3, SEED 24 9 / GROW 3, GROW 9, SEED 35 7 / GROW 3, GROW 7, SEED 35 6
+24 36 / - / - / GROW 24 / GROW 36, SEED 24 3 / GROW 3, SEED 24 9 / GROW 3, GROW 9, SEED 36 1 / GROW 3, GROW 1, SEED 36 20
+25 26 / - / - / SEED 26 12 / GROW 25, GROW 12 / GROW 12, SEED 25 10 / GROW 10, SEED 12 0 / GROW 10, GROW 0, SEED 12 14
+25 27 / - / - / SEED 27 12 / GROW 25, GROW 12 / GROW 12, SEED 25 10 / GROW 10, SEED 12 0 / GROW 10, GROW 0, SEED 12 14
+25 28 / - / - / GROW 25 / GROW 28, SEED 25 10 / GROW 10, SEED 25 12 / GROW 10, GROW 12, SEED 28 14 / GROW 12, GROW 14, SEED 10 0
+25 29 / - / - / GROW 25 / GROW 29, SEED 25 10 / GROW 10, SEED 29 15 / GROW 10, GROW 15, SEED 29 4 / GROW 15, GROW 4, SEED 10 22
+25 30 / - / - / GROW 25 / GROW 30, SEED 25 10 / GROW 10, SEED 25 12 / GROW 10, GROW 12, SEED 30 5 / GROW 5, GROW 10, SEED 30 32
+25 31 / - / - / GROW 25 / GROW 31, SEED 25 10 / GROW 10, SEED 25 12 / GROW 10, GROW 12, SEED 31 16 / GROW 12, GROW 16, SEED 10 0
+25 32 / - / - / GROW 25 / GROW 32, SEED 25 12 / GROW 12, SEED 32 30 / GROW 12, GROW 30, SEED 32 5 / GROW 30, GROW 5, SEED 12 2
+25 33 / - / - / GROW 25 / GROW 33, SEED 25 12 / GROW 12, SEED 33 6 / GROW 12, GROW 6, SEED 33 15 / GROW 6, GROW 15, SEED 12 28
+25 35 / - / - / - / GROW 25 / GROW 35, SEED 25 10 / GROW 10, SEED 35 6 / GROW 10, GROW 6, SEED 35 7
+25 36 / - / - / GROW 25 / GROW 36, SEED 25 10 / GROW 10, SEED 36 20 / GROW 10, GROW 20, SEED 36 1 / GROW 20, GROW 1, SEED 10 4
+26 27 / - / - / SEED 27 13 / GROW 26, GROW 13 / GROW 13, SEED 26 3 / GROW 3, SEED 13 5 / GROW 3, GROW 5, SEED 13 30
+26 28 / - / - / SEED 28 13 / GROW 26, GROW 13 / GROW 13, SEED 26 3 / GROW 3, SEED 13 5 / GROW 3, GROW 5, SEED 13 30
+26 29 / - / - / GROW 26 / GROW 29, SEED 26 3 / GROW 3, SEED 26 24 / GROW 3, GROW 24, SEED 29 15 / GROW 24, GROW 15, SEED 3 1
+26 30 / - / - / GROW 26 / GROW 30, SEED 26 3 / GROW 3, SEED 26 13 / GROW 3, GROW 13, SEED 30 5 / GROW 13, GROW 5, SEED 3 1
+26 31 / - / - / GROW 26 / GROW 31, SEED 26 3 / GROW 3, SEED 26 13 / GROW 3, GROW 13, SEED 26 24 / GROW 13, GROW 24, SEED 3 1
+26 32 / - / - / GROW 26 / GROW 32, SEED 26 3 / GROW 3, SEED 26 13 / GROW 3, GROW 13, SEED 32 5 / GROW 3, GROW 5, SEED 32 33
+26 33 / - / - / GROW 26 / GROW 33, SEED 26 3 / GROW 3, SEED 26 13 / GROW 3, GROW 13, SEED 33 15 / GROW 3, GROW 15, SEED 33 6
+26 34 / - / - / GROW 26 / GROW 34, SEED 26 3 / GROW 3, SEED 26 13 / GROW 3, GROW 13, SEED 34 16 / GROW 13, GROW 16, SEED 3 1
+26 36 / - / - / - / GROW 26 / GROW 36, SEED 26 3 / GROW 3, SEED 36 1 / GROW 3, GROW 1, SEED 26 24
+27 28 / - / - / SEED 28 29 / GROW 27, GROW 29 / GROW 29, SEED 27 4 / GROW 4, SEED 27 11 / GROW 4, GROW 11, SEED 29 15
+27 29 / - / - / GROW 27 / GROW 29, SEED 27 4 / GROW 4, SEED 27 11 / GROW 4, GROW 11, SEED 29 15 / GROW 11, GROW 15, SEED 4 2
+27 30 / - / - / GROW 27 / GROW 30, SEED 27 11 / GROW 11, SEED 30 5 / GROW 11, GROW 5, SEED 30 32 / GROW 5, GROW 32, SEED 11 23
+27 31 / - / - / GROW 27 / GROW 31, SEED 27 4 / GROW 4, SEED 27 11 / GROW 4, GROW 11, SEED 27 29 / GROW 11, GROW 29, SEED 4 2
+27 32 / - / - / GROW 27 / GROW 32, SEED 27 4 / GROW 4, SEED 27 11 / GROW 4, GROW 11, SEED 27 29 / GROW 4, GROW 29, SEED 32 17
+27 33 / - / - / GROW 27 / GROW 33, SEED 27 4 / GROW 4, SEED 27 29 / GROW 4, GROW 29, SEED 33 6 / GROW 29, GROW 6, SEED 4 2
+27 34 / - / - / GROW 27 / GROW 34, SEED 27 4 / GROW 4, SEED 27 29 / GROW 4, GROW 29, SEED 34 6 / GROW 29, GROW 6, SEED 4 2
+27 35 / - / - / GROW 27 / GROW 35, SEED 27 4 / GROW 4, SEED 27 29 / GROW 4, GROW 29, SEED 35 6 / GROW 29, GROW 6, SEED 4 2
+28 29 / - / - / SEED 29 14 / GROW 29, GROW 14, SEED 28 27 / GROW 27 / GROW 27, SEED 29 31 / GROW 31, SEED 27 11
+28 30 / - / - / SEED 30 14 / GROW 28, GROW 14 / GROW 14, SEED 28 12 / GROW 12, SEED 14 0 / GROW 12, GROW 0, SEED 14 16
+28 31 / - / - / GROW 28 / GROW 31, SEED 28 12 / GROW 12, SEED 28 14 / GROW 12, GROW 14, SEED 31 16 / GROW 14, GROW 16, SEED 12 0
+28 32 / - / - / GROW 28 / GROW 32, SEED 28 12 / GROW 12, SEED 32 17 / GROW 12, GROW 17, SEED 32 5 / GROW 17, GROW 5, SEED 12 25
+28 33 / - / - / GROW 28 / GROW 33, SEED 28 12 / GROW 12, SEED 28 14 / GROW 12, GROW 14, SEED 33 6 / GROW 6, GROW 12, SEED 33 35
+28 34 / - / - / GROW 28 / GROW 34, SEED 28 12 / GROW 12, SEED 28 14 / GROW 12, GROW 14, SEED 34 18 / GROW 14, GROW 18, SEED 12 0
+28 35 / - / - / GROW 28 / GROW 35, SEED 28 14 / GROW 14, SEED 35 33 / GROW 14, GROW 33, SEED 35 1 / GROW 33, GROW 1, SEED 14 3
+28 36 / - / - / GROW 28 / GROW 36, SEED 28 14 / GROW 14, SEED 36 1 / GROW 14, GROW 1, SEED 36 17 / GROW 1, GROW 17, SEED 14 31
+29 30 / - / - / SEED 30 15 / GROW 30, GROW 15, SEED 29 13 / GROW 13 / GROW 15 / GROW 15, SEED 30 5
+29 31 / - / - / SEED 31 15 / GROW 29, GROW 15 / GROW 15, SEED 29 4 / GROW 4, SEED 15 6 / GROW 4, GROW 6, SEED 15 33
+29 32 / - / - / GROW 29 / GROW 32, SEED 29 4 / GROW 4, SEED 29 27 / GROW 4, GROW 27, SEED 32 17 / GROW 27, GROW 17, SEED 4 2
+29 33 / - / - / GROW 29 / GROW 33, SEED 29 4 / GROW 4, SEED 29 15 / GROW 4, GROW 15, SEED 33 6 / GROW 15, GROW 6, SEED 4 2
+29 34 / - / - / GROW 29 / GROW 34, SEED 29 4 / GROW 4, SEED 29 15 / GROW 4, GROW 15, SEED 29 27 / GROW 15, GROW 27, SEED 4 2
+29 35 / - / - / GROW 29 / GROW 35, SEED 29 4 / GROW 4, SEED 29 15 / GROW 4, GROW 15, SEED 35 6 / GROW 4, GROW 6, SEED 35 36
+29 36 / - / - / GROW 29 / GROW 36, SEED 29 4 / GROW 4, SEED 29 15 / GROW 4, GROW 15, SEED 36 17 / GROW 4, GROW 17, SEED 36 1
+30 31 / - / - / SEED 31 32 / GROW 31, GROW 32, SEED 30 14 / GROW 14 / - / GROW 31, GROW 30, SEED 32 33
+30 32 / - / - / GROW 30 / GROW 32, SEED 30 5 / GROW 5, SEED 30 13 / GROW 5, GROW 13, SEED 32 17 / GROW 13, GROW 17, SEED 5 1
+30 33 / - / - / GROW 30 / GROW 33, SEED 30 13 / GROW 13, SEED 33 6 / GROW 13, GROW 6, SEED 33 35 / GROW 6, GROW 35, SEED 13 26
+30 34 / - / - / GROW 30 / GROW 34, SEED 30 5 / GROW 5, SEED 30 13 / GROW 5, GROW 13, SEED 30 32 / GROW 13, GROW 32, SEED 5 1
+30 35 / - / - / GROW 30 / GROW 35, SEED 30 5 / GROW 5, SEED 30 13 / GROW 5, GROW 13, SEED 30 32 / GROW 5, GROW 32, SEED 35 7
+30 36 / - / - / GROW 30 / GROW 36, SEED 30 5 / GROW 5, SEED 30 32 / GROW 5, GROW 32, SEED 36 1 / GROW 32, GROW 1, SEED 5 3
+31 32 / - / - / SEED 31 30 / GROW 31, GROW 30, SEED 32 33 / GROW 33, SEED 31 5 / GROW 5, SEED 30 29 / GROW 31
+31 33 / - / - / SEED 31 15 / GROW 33, GROW 15 / GROW 15, SEED 33 6 / GROW 6, SEED 15 4 / GROW 6, GROW 4, SEED 15 29
+31 34 / - / - / GROW 31 / GROW 34, SEED 31 14 / GROW 14, SEED 31 16 / GROW 14, GROW 16, SEED 34 18 / GROW 16, GROW 18, SEED 14 0
+31 35 / - / - / GROW 31 / GROW 35, SEED 31 14 / GROW 14, SEED 35 7 / GROW 14, GROW 7, SEED 35 6 / GROW 7, GROW 6, SEED 14 28
+31 36 / - / - / GROW 31 / GROW 36, SEED 31 14 / GROW 14, SEED 31 16 / GROW 14, GROW 16, SEED 36 1 / GROW 1, GROW 14, SEED 36 20
+32 33 / - / - / SEED 32 15 / GROW 32, GROW 15, SEED 33 34 / GROW 34, SEED 32 6 / GROW 6, SEED 15 14 / GROW 32
+32 34 / - / - / SEED 32 15 / GROW 34, GROW 15 / GROW 15, SEED 34 18 / GROW 18, SEED 15 4 / GROW 18, GROW 4, SEED 15 29
+32 35 / - / - / GROW 32 / GROW 35, SEED 32 5 / GROW 5, SEED 32 30 / GROW 5, GROW 30, SEED 35 7 / GROW 30, GROW 7, SEED 5 3
+32 36 / - / - / GROW 32 / GROW 36, SEED 32 5 / GROW 5, SEED 32 17 / GROW 5, GROW 17, SEED 36 1 / GROW 17, GROW 1, SEED 5 3
+33 34 / - / - / SEED 33 16 / GROW 33, GROW 16, SEED 34 35 / GROW 35 / GROW 35 / GROW 33, SEED 16 5
+33 35 / - / - / GROW 33 / GROW 35, SEED 33 6 / GROW 6, SEED 33 15 / GROW 6, GROW 15, SEED 35 7 / GROW 15, GROW 7, SEED 6 2
+33 36 / - / - / GROW 33 / GROW 36, SEED 33 15 / GROW 15, SEED 36 1 / GROW 15, GROW 1, SEED 36 20 / GROW 1, GROW 20, SEED 15 29
+34 35 / - / - / SEED 34 33 / GROW 34, GROW 33, SEED 35 18 / GROW 18 / GROW 35 / GROW 35, SEED 18 1
+34 36 / - / - / SEED 34 17 / GROW 36, GROW 17 / GROW 17, SEED 36 1 / GROW 1, SEED 17 5 / GROW 1, GROW 5, SEED 17 32
+35 36 / - / - / SEED 35 17 / GROW 35, GROW 17, SEED 36 7 / GROW 7 / GROW 36 / GROW 35, SEED 17 16
+";
+
+/// Layout of the board at the start of the game
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Opening {
+    pub trees: Vec<i32>,
+    pub unusable: Vec<i32>,
+}
+
+impl Opening {
+    pub fn new(board: &Board) -> Opening {
+        let cells = |keep: fn(&Cell) -> bool| {
+            board
+                .board
+                .iter()
+                .filter(|cell| keep(cell))
+                .map(|cell| cell.index)
+                .collect()
+        };
+        Opening {
+            trees: cells(|cell| cell.tree.as_ref().is_some_and(|t| t.is_mine)),
+            unusable: cells(|cell| cell.richness == 0),
+        }
+    }
+
+    /// Every start the server deals on a board without unusable cells: our
+    /// two trees on the outer ring, the opponent's on the opposite cells
+    pub fn starts() -> Vec<Opening> {
+        let layout = Layout::new();
+        let edge = (0..layout.len())
+            .filter(|&i| layout.distance(0, i) == BOARD_RADIUS)
+            .collect::<Vec<_>>();
+        let mut starts = Vec::new();
+        for (i, &a) in edge.iter().enumerate() {
+            for &b in edge[i + 1..].iter() {
+                if b != opposite(&layout, a) {
+                    starts.push(Opening {
+                        trees: vec![a as i32, b as i32],
+                        unusable: Vec::new(),
+                    });
+                }
+            }
+        }
+        starts
+    }
+
+    /// Board of the first day, our trees and the opponent's small
+    pub fn board(&self) -> Board {
+        let mut board = Board::full();
+        for &cell in self.unusable.iter() {
+            board.board[cell as usize].richness = 0;
+        }
+        for &cell in self.trees.iter() {
+            let opposite = opposite(&board.layout, cell as usize) as i32;
+            for (cell_index, is_mine) in [(cell, true), (opposite, false)] {
+                board.update_tree(Tree {
+                    cell_index,
+                    size: 1,
+                    is_mine,
+                    is_dormant: false,
+                });
+            }
+        }
+        board
+    }
+}
+
+/// Cell the opponent's tree mirroring ours at `index` is on
+fn opposite(layout: &Layout, index: usize) -> usize {
+    let opposite = layout.index(layout.hex(index) * -1);
+    opposite.expect("the board is point symmetric")
+}
+
+fn cells(words: &mut Words) -> Result<Vec<i32>, ParseError> {
+    if words.line() == "-" {
+        return Ok(Vec::new());
+    }
+    words.rest("cell")
+}
+
+fn list(cells: &[i32]) -> String {
+    if cells.is_empty() {
+        return "-".to_string();
+    }
+    let cells = cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    cells.join(" ")
+}
+
+#[derive(Debug, Default)]
+pub struct Book {
+    lines: BTreeMap<Opening, Vec<Vec<Action>>>,
+}
+
+impl Book {
+    pub fn parse(text: &str) -> Result<Book, ParseError> {
+        let mut book = Book::default();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut parts = line.split('/').map(|part| Words::new(i + 1, part.trim()));
+            let (Some(mut trees), Some(mut unusable)) = (parts.next(), parts.next()) else {
+                return Err(Words::new(i + 1, line).error("unusable cells", ErrorKind::Missing));
+            };
+            let opening = Opening {
+                trees: cells(&mut trees)?,
+                unusable: cells(&mut unusable)?,
+            };
+            let mut days = Vec::new();
+            for day in parts {
+                let actions = day
+                    .line()
+                    .split(',')
+                    .filter(|action| action.trim() != "-")
+                    .map(|action| Action::from_line(&mut Words::new(i + 1, action.trim())))
+                    .collect::<Result<Vec<_>, _>>()?;
+                days.push(actions);
+            }
+            book.lines.insert(opening, days);
+        }
+        Ok(book)
+    }
+
+    /// The lines written below, none when they don't parse
+    pub fn builtin() -> Book {
+        Book::parse(BOOK).unwrap_or_else(|e| {
+            eprintln!("opening book {}", e);
+            Book::default()
+        })
+    }
+
+    /// Actions of every day of the line, the line of our trees on a board
+    /// without unusable cells when the layout has none of its own, none
+    /// out of the book
+    pub fn line(&self, opening: &Opening) -> Vec<Vec<Action>> {
+        let trees = Opening {
+            trees: opening.trees.clone(),
+            unusable: Vec::new(),
+        };
+        let line = self.lines.get(opening).or_else(|| self.lines.get(&trees));
+        line.cloned().unwrap_or_default()
+    }
+
+    /// Book line of the board of the first day, played action by action by
+    /// a search wider than the one of the game, the opponent waiting
+    pub fn write_line(board: &Board) -> String {
+        let beam = Beam {
+            width: 100,
+            budget: Duration::from_secs(600),
+            ..Beam::default()
+        };
+        let wait = Action::new(ActionKind::Wait, -1, -1);
+        let mut state = State::start(board.clone());
+        state.players[1].waiting = true;
+        let mut played = Vec::new();
+        while state.day < BOOK_DAYS {
+            let action = beam.search(&state);
+            state.play([&action, &wait]);
+            played.push(action);
+        }
+        let opening = Opening::new(board);
+        let mut line = vec![list(&opening.trees), list(&opening.unusable)];
+        let mut day = Vec::new();
+        for action in played {
+            if action.kind != ActionKind::Wait {
+                day.push(action.action_string);
+                continue;
+            }
+            line.push(if day.is_empty() {
+                "-".to_string()
+            } else {
+                day.join(", ")
+            });
+            day.clear();
+        }
+        line.join(" / ")
+    }
+}
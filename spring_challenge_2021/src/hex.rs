@@ -0,0 +1,145 @@
+//! Cube coordinates of the hexagonal board.
+//!
+//! Cell 0 is the center and the other cells spiral outwards ring by ring.
+//! Directions are numbered like the neighbours sent by the referee, 0 is
+//! east and they turn anticlockwise, the sun moves the same way.
+
+use std::ops::{Add, Mul};
+
+pub const BOARD_RADIUS: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex::new(0, 0, 0);
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex::new(1, -1, 0),
+        Hex::new(1, 0, -1),
+        Hex::new(0, 1, -1),
+        Hex::new(-1, 1, 0),
+        Hex::new(-1, 0, 1),
+        Hex::new(0, -1, 1),
+    ];
+
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn neighbour(self, direction: usize) -> Hex {
+        self + Hex::DIRECTIONS[direction % 6]
+    }
+
+    pub fn distance(self, other: Hex) -> i32 {
+        ((self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()) / 2
+    }
+
+    /// Hexes at exactly `radius` from this one, starting east and turning
+    /// anticlockwise like the cell indices
+    pub fn ring(self, radius: i32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let mut hex = self + Hex::DIRECTIONS[0] * radius;
+        for direction in 0..6 {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.neighbour(direction + 2);
+            }
+        }
+        ring
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, factor: i32) -> Hex {
+        Hex::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+/// Coordinates of the cell indices and the neighbour table built from them
+#[derive(Debug, Clone)]
+pub struct Layout {
+    coords: Vec<Hex>,
+    neighbours: Vec<[Option<usize>; 6]>,
+}
+
+impl Layout {
+    pub fn new() -> Self {
+        let coords = (0..=BOARD_RADIUS)
+            .flat_map(|radius| Hex::ORIGIN.ring(radius))
+            .collect::<Vec<_>>();
+        let mut layout = Self {
+            coords,
+            neighbours: Vec::new(),
+        };
+        layout.neighbours = layout
+            .coords
+            .iter()
+            .map(|&hex| [0, 1, 2, 3, 4, 5].map(|d| layout.index(hex.neighbour(d))))
+            .collect();
+        layout
+    }
+
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    pub fn hex(&self, index: usize) -> Hex {
+        self.coords[index]
+    }
+
+    /// Index of the cell at `hex`, `None` off the board
+    pub fn index(&self, hex: Hex) -> Option<usize> {
+        self.coords.iter().position(|&c| c == hex)
+    }
+
+    pub fn neighbour(&self, index: usize, direction: usize) -> Option<usize> {
+        self.neighbours[index][direction % 6]
+    }
+
+    pub fn distance(&self, a: usize, b: usize) -> i32 {
+        self.hex(a).distance(self.hex(b))
+    }
+
+    /// Up to `length` cells after `index` going in `direction`, stopping at
+    /// the edge of the board
+    pub fn line(
+        &self,
+        index: usize,
+        direction: usize,
+        length: i32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let mut next = Some(index);
+        (0..length).map_while(move |_| {
+            next = self.neighbour(next?, direction);
+            next
+        })
+    }
+
+    /// Cells at most `radius` away from `index`, itself excluded
+    pub fn within(&self, index: usize, radius: i32) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(move |&i| i != index && self.distance(index, i) <= radius)
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,649 @@
+mod book;
+mod hex;
+mod moves;
+mod search;
+mod simulator;
+mod sun;
+#[cfg(test)]
+mod tests;
+
+use std::cmp;
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+
+use book::{Book, Opening};
+use cg_common::{Console, ErrorKind, FromLine, ParseError, Words};
+use hex::Layout;
+use search::Beam;
+use simulator::State;
+
+// Plan
+// - add enum for richness values to consts
+// - parse actions
+// - implement board - distance between cells (if needed since given list of actions)
+
+// Strat notes
+// - prio seed (correct slot) > grow > harvest > wait
+// - some kind of simulation is needed. if I don't gain enough to make difference by having a tree, then it should be completed, otherwise the enemy removes nutritions
+// - spawning seems to be on the outer ring, with 2 trees of level 1.
+// - grow trees in positions so they dont cast shadows on each other - one step in direction and then one more in dir +1 or -1
+// - make sure to harvest some trees early
+// - never harvest all trees unless end of game - check if harvesting gains more points than keeping sun points (harvest all athe end?)
+
+// TODO
+// - create and action struct
+// - action should self evaluate itself somehow and return Option(value), if returned None then "WAIT"
+// - think of harvesting strat
+// - add initial steps
+
+// Constants
+const TREE_LIFECYCLE_COST: i32 = 4;
+const FOREST_INITIAL_NUTRIENT: i32 = 20;
+const POINTS_PER_3SUN: i32 = 1;
+const GAME_LENGTH: i32 = 24;
+const BOARD_SIZE: i32 = 37;
+
+// Settings
+const MIN_TREE3_N: i32 = 3;
+const MAX_TREE0_N: i32 = 3;
+const MAX_TREES: i32 = 12;
+const SUN_HORIZON: i32 = 6; // days of sun weighed for each action, a full turn of the sun
+const SEED_MIN_SUN: i32 = 5; // a seed has to stay out of the shadows most of the horizon
+
+// Struct Cell
+#[derive(Clone)]
+struct Cell {
+    index: i32,
+    richness: i32,
+    tree: Option<Tree>,
+    neighbours: [i32; 6],
+}
+
+impl FromLine for Cell {
+    fn from_line(words: &mut Words) -> Result<Cell, ParseError> {
+        let index = words.field("index")?; // 0 is the center cell, the next cells spiral outwards
+        let richness = words.field("richness")?; // 0 if the cell is unusable, 1-3 for usable cells
+        let mut neighbours = [-1; 6]; // the index of the neighbouring cell for each direction
+        for neighbour in neighbours.iter_mut() {
+            *neighbour = words.field("neighbour")?;
+        }
+        Ok(Cell {
+            index,
+            richness,
+            tree: None,
+            neighbours,
+        })
+    }
+}
+
+impl Cell {
+    pub fn update_tree(&mut self, tree: Tree) {
+        self.tree = Some(tree);
+    }
+
+    pub fn reset_tree(&mut self) {
+        self.tree = None;
+    }
+
+    pub fn get_tree_size(&self) -> i32 {
+        match &self.tree {
+            Some(tree) => tree.size,
+            None => -1,
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(tree) = &self.tree {
+            write!(
+                f,
+                "Cell: {}\nRich: {}\nTree: {}\n",
+                self.index, self.richness, tree
+            )
+        } else {
+            write!(
+                f,
+                "Cell: {}\nRich: {}\nTree: None\n",
+                self.index, self.richness
+            )
+        }
+    }
+}
+
+// Board
+#[derive(Clone)]
+struct Board {
+    board: Vec<Cell>,
+    layout: Layout,
+}
+
+impl Board {
+    pub fn new(console: &mut Console) -> Result<Board, ParseError> {
+        let mut new_board: Board = Board {
+            board: Vec::with_capacity(BOARD_SIZE as usize),
+            layout: Layout::new(),
+        };
+        for index in 0..BOARD_SIZE as usize {
+            let mut words = console.words("cell")?;
+            let cell = Cell::from_line(&mut words)?;
+            if cell.index != index as i32 {
+                let reason = format!("cell {} comes in place of cell {}", cell.index, index);
+                return Err(words.error("index", ErrorKind::Invalid(reason)));
+            }
+            // shadows and seed ranges are walked on the cube coordinates
+            let expected = (0..6).map(|d| {
+                new_board
+                    .layout
+                    .neighbour(index, d)
+                    .map_or(-1, |n| n as i32)
+            });
+            if !cell.neighbours.iter().copied().eq(expected) {
+                let reason = "they do not match the hex layout".to_string();
+                return Err(words.error("neighbours", ErrorKind::Invalid(reason)));
+            }
+            new_board.board.push(cell);
+        }
+        Ok(new_board)
+    }
+
+    /// Board of the official maps before the unusable cells are dealt,
+    /// richness 3 in the middle down to 1 on the outer ring and no tree
+    pub fn full() -> Board {
+        let layout = Layout::new();
+        let board = (0..layout.len())
+            .map(|index| Cell {
+                index: index as i32,
+                richness: 3 - (layout.distance(0, index) - 1).max(0),
+                tree: None,
+                neighbours: [0, 1, 2, 3, 4, 5]
+                    .map(|d| layout.neighbour(index, d).map_or(-1, |n| n as i32)),
+            })
+            .collect();
+        Board { board, layout }
+    }
+
+    #[allow(dead_code)]
+    pub fn print_dbg(&self) {
+        for cell in self.board.iter() {
+            eprintln!("{}", cell);
+        }
+    }
+
+    pub fn get_cell_richness_points(&self, index: usize) -> i32 {
+        let value = (self.board[index].richness - 1) * 2;
+        cmp::max(0, value)
+    }
+
+    pub fn reset_trees(&mut self) {
+        for cell in self.board.iter_mut() {
+            cell.reset_tree();
+        }
+    }
+
+    pub fn update_tree(&mut self, tree: Tree) {
+        self.board[tree.cell_index as usize].update_tree(tree);
+    }
+
+    pub fn get_tree_size(&self, index: usize) -> i32 {
+        self.board[index].get_tree_size()
+    }
+
+    // cells a tree of this size can seed, usable and without a tree
+    pub fn seed_targets(&self, origin: usize, size: i32) -> Vec<usize> {
+        self.layout
+            .within(origin, size)
+            .filter(|&i| self.board[i].richness > 0 && self.board[i].tree.is_none())
+            .collect()
+    }
+}
+
+// Tree
+#[derive(Debug, Clone)]
+struct Tree {
+    cell_index: i32,
+    size: i32,
+    is_mine: bool,
+    is_dormant: bool,
+}
+
+impl FromLine for Tree {
+    fn from_line(words: &mut Words) -> Result<Tree, ParseError> {
+        Ok(Tree {
+            cell_index: words.field("cell_index")?, // location of this tree
+            size: words.field("size")?,             // size of this tree: 0-3
+            is_mine: words.flag("is_mine")?,        // 1 if this is your tree
+            is_dormant: words.flag("is_dormant")?,  // 1 if this tree is dormant
+        })
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tree: {}\nSize: {}\nis_mine: {}\nis_dormant: {}",
+            self.cell_index, self.size, self.is_mine, self.is_dormant
+        )
+    }
+}
+
+// Player - is it needed?
+#[derive(Clone)]
+struct Player {
+    sun: i32,
+    score: i32,
+    waiting: bool,
+}
+
+impl Player {
+    pub fn new() -> Player {
+        Player {
+            sun: 0,
+            score: 0,
+            waiting: false,
+        }
+    }
+
+    pub fn update(&mut self, console: &mut Console) -> Result<(), ParseError> {
+        let mut inputs = console.words("player")?;
+        self.sun = inputs.field("sun")?;
+        self.score = inputs.field("score")?;
+        // only the opponent line tells whether they are waiting
+        self.waiting = inputs.remaining() > 0 && inputs.flag("waiting")?;
+        Ok(())
+    }
+}
+
+// Actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ActionKind {
+    Wait,
+    Grow,
+    Seed,
+    Complete,
+}
+
+impl FromStr for ActionKind {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<ActionKind, Self::Err> {
+        match s {
+            "WAIT" => Ok(ActionKind::Wait),
+            "GROW" => Ok(ActionKind::Grow),
+            "SEED" => Ok(ActionKind::Seed),
+            "COMPLETE" => Ok(ActionKind::Complete),
+            _ => Err("is not WAIT, GROW, SEED or COMPLETE"),
+        }
+    }
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            ActionKind::Wait => "WAIT",
+            ActionKind::Grow => "GROW",
+            ActionKind::Seed => "SEED",
+            ActionKind::Complete => "COMPLETE",
+        };
+        write!(f, "{}", command)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Action {
+    action_string: String,
+    kind: ActionKind,
+    cell_index: i32,
+    target_index: i32,
+}
+
+impl FromLine for Action {
+    fn from_line(words: &mut Words) -> Result<Action, ParseError> {
+        let mut action = Action {
+            action_string: words.line().to_string(),
+            kind: words.field("command")?,
+            cell_index: -1,
+            target_index: -1,
+        };
+        if words.remaining() > 0 {
+            action.cell_index = words.field("cell_index")?;
+            if words.remaining() > 0 {
+                action.target_index = words.field("target_index")?;
+            }
+        }
+        Ok(action)
+    }
+}
+
+impl Action {
+    // -1 for the indices the command does not take
+    pub fn new(kind: ActionKind, cell_index: i32, target_index: i32) -> Action {
+        let action_string = match kind {
+            ActionKind::Wait => kind.to_string(),
+            ActionKind::Seed => format!("{} {} {}", kind, cell_index, target_index),
+            _ => format!("{} {}", kind, cell_index),
+        };
+        Action {
+            action_string,
+            kind,
+            cell_index,
+            target_index,
+        }
+    }
+
+    // false when replaying and the recorded action differs
+    pub fn exec(&self, console: &mut Console) -> bool {
+        console.write_turn(&format!("{}\n", self.action_string))
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Action: {}\nOrigin: {}\nTarget: {}",
+            self.kind, self.cell_index, self.target_index
+        )
+    }
+}
+
+// Game
+struct Game {
+    day: i32,
+    nutrients: i32,
+    board: Board,
+    trees: Vec<Tree>,
+    actions: Vec<Action>,
+    /// Moves the referee lists, only used to check `actions`
+    listed: Vec<Action>,
+    me: Player,
+    opponent: Player,
+    ntree0: i32,
+    ntree1: i32,
+    ntree2: i32,
+    ntree3: i32,
+    /// Days of the book line being followed, cleared once it can't be
+    line: Vec<Vec<Action>>,
+    /// Day and number of the book actions played on it
+    line_played: (i32, usize),
+}
+
+impl Game {
+    pub fn new(console: &mut Console) -> Result<Game, ParseError> {
+        let number_of_cells: i32 = console.parse("number_of_cells")?;
+        assert!(
+            number_of_cells == BOARD_SIZE,
+            "Board size input is not default."
+        );
+        // Read Board
+        let board: Board = Board::new(console)?;
+        // Return Game Struct
+        Ok(Game {
+            day: 0,
+            nutrients: FOREST_INITIAL_NUTRIENT,
+            board,
+            trees: Vec::new(),
+            actions: Vec::new(),
+            listed: Vec::new(),
+            me: Player::new(),
+            opponent: Player::new(),
+            ntree0: 0,
+            ntree1: 0,
+            ntree2: 0,
+            ntree3: 0,
+            line: Vec::new(),
+            line_played: (0, 0),
+        })
+    }
+
+    // returns false once the input has ended
+    pub fn update(&mut self, console: &mut Console) -> Result<bool, ParseError> {
+        // self.reset();
+
+        let Some(mut day) = console.next_words()? else {
+            return Ok(false);
+        };
+        self.day = day.field("day")?; // the game lasts 24 days: 0-23
+        self.nutrients = console.parse("nutrients")?; // the base score you gain from the next COMPLETE action
+
+        self.me.update(console)?;
+        self.opponent.update(console)?;
+
+        let number_of_trees: usize = console.parse("number_of_trees")?; // the current amount of trees
+        for _ in 0..number_of_trees {
+            let new_tree: Tree = console.read()?;
+            if new_tree.is_mine {
+                match new_tree.size {
+                    0 => self.ntree0 += 1,
+                    1 => self.ntree1 += 1,
+                    2 => self.ntree2 += 1,
+                    3 => self.ntree3 += 1,
+                    _ => {}
+                }
+            }
+            self.board.update_tree(new_tree);
+        }
+
+        let number_of_possible_moves: usize = console.parse("number_of_possible_moves")?;
+        self.listed = Vec::with_capacity(number_of_possible_moves);
+        for _ in 0..number_of_possible_moves {
+            self.listed.push(console.read()?);
+        }
+        // the moves are worked out from the state, the list sent only checks them
+        self.actions = State::new(self).legal_actions(0);
+        let (missing, unexpected) = self.action_mismatch();
+        if !missing.is_empty() || !unexpected.is_empty() {
+            eprintln!(
+                "day {}: legal actions missing {:?}, unexpected {:?}",
+                self.day, missing, unexpected
+            );
+        }
+        Ok(true)
+    }
+
+    /// Listed moves we don't generate, and generated moves that aren't
+    /// listed
+    pub fn action_mismatch(&self) -> (Vec<String>, Vec<String>) {
+        let strings = |actions: &[Action]| {
+            actions
+                .iter()
+                .map(|a| a.action_string.clone())
+                .collect::<BTreeSet<_>>()
+        };
+        let (ours, theirs) = (strings(&self.actions), strings(&self.listed));
+        (
+            theirs.difference(&ours).cloned().collect(),
+            ours.difference(&theirs).cloned().collect(),
+        )
+    }
+
+    pub fn reset(&mut self) {
+        self.trees.clear();
+        self.actions.clear();
+        self.listed.clear();
+        self.ntree0 = 0;
+        self.ntree1 = 0;
+        self.ntree2 = 0;
+        self.ntree3 = 0;
+        self.board.reset_trees();
+    }
+
+    pub fn naive_move(&mut self, console: &mut Console) -> bool {
+        let mut gain: i32 = -2 * self.me.sun;
+        let mut action_index: usize = 0;
+        for (i, action) in self.actions.iter().enumerate() {
+            let mut current_gain = -2 * self.me.sun;
+            // sun we win and the opponent loses over the next days
+            let sun = self.board.marginal_sun(action, self.day, SUN_HORIZON);
+            let sun_gain = sun[0] - sun[1];
+            if action.kind == ActionKind::Complete && (self.day > 20 || self.ntree3 > MIN_TREE3_N) {
+                current_gain = self.nutrients;
+                current_gain += self
+                    .board
+                    .get_cell_richness_points(action.cell_index as usize);
+                current_gain += sun_gain;
+            } else if action.kind == ActionKind::Grow {
+                let size = self.get_tree_size(action.cell_index);
+                let cost: i32 = match size {
+                    0 => 1 + self.ntree1,
+                    1 => 3 + self.ntree2,
+                    2 => 7 + self.ntree3,
+                    _ => 1000000,
+                };
+                // eprintln!("{} for {} ({})", action.action_string, cost, self.me.sun);
+                current_gain = self
+                    .board
+                    .get_cell_richness_points(action.cell_index as usize)
+                    - cost
+                    + size
+                    + sun_gain;
+            } else if action.kind == ActionKind::Wait {
+                current_gain = -2 * self.me.sun + 1;
+            } else if action.kind == ActionKind::Seed
+                && self.get_tree_size(action.cell_index) > 1
+                && self.ntree0 < MAX_TREE0_N
+                && (self.ntree0 + self.ntree1 + self.ntree2 + self.ntree3) < MAX_TREES
+                && sun_gain >= SEED_MIN_SUN
+            {
+                current_gain = self
+                    .board
+                    .get_cell_richness_points(action.target_index as usize)
+                    - self.ntree0;
+            }
+            // eprintln!("{} gains {} ({})", action.action_string, current_gain, gain);
+            if current_gain > gain {
+                gain = current_gain;
+                action_index = i;
+            }
+        }
+        self.actions[action_index].exec(console)
+    }
+
+    pub fn search_move(&mut self, beam: &Beam, console: &mut Console) -> bool {
+        let action = beam.search(&State::new(self));
+        action.exec(console)
+    }
+
+    fn get_tree_size(&self, cell: i32) -> i32 {
+        self.board.get_tree_size(cell as usize)
+    }
+
+    /// Looks the starting layout up in the book, on the first turn
+    pub fn open_book(&mut self, book: &Book) {
+        self.line = book.line(&Opening::new(&self.board));
+        self.line_played = (0, 0);
+    }
+
+    /// Next action of the book line, `None` once out of the book
+    pub fn book_move(&mut self, console: &mut Console) -> Option<bool> {
+        let actions = self.line.get(self.day as usize)?;
+        if self.line_played.0 != self.day {
+            self.line_played = (self.day, 0);
+        }
+        let action = match actions.get(self.line_played.1) {
+            None => Action::new(ActionKind::Wait, -1, -1),
+            Some(next) => {
+                let legal = self
+                    .actions
+                    .iter()
+                    .find(|a| a.action_string == next.action_string);
+                let Some(action) = legal.cloned() else {
+                    eprintln!("{} can't be played, leaving the book", next.action_string);
+                    self.line.clear();
+                    return None;
+                };
+                action
+            }
+        };
+        self.line_played.1 += 1;
+        Some(action.exec(console))
+    }
+}
+
+/**
+ * Auto-generated code below aims at helping you parse
+ * the standard input according to the problem statement.
+ **/
+fn main() {
+    // `replay <file> [turn]` stops after the given turn, `naive` plays the
+    // one action heuristic instead of the search, `--width <n>`,
+    // `--days <n>` and `--budget <ms>` tune the search, `nobook` plays
+    // without the opening book, `book <file>` prints the book line of a
+    // recorded game and `book` the lines of every start the server deals
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "book") {
+        if let Some(path) = args.get(1) {
+            let recording = fs::read_to_string(path).unwrap();
+            let mut console = Console::replay(&recording);
+            let mut game = Game::new(&mut console).unwrap_or_else(|e| e.exit());
+            game.update(&mut console).unwrap_or_else(|e| e.exit());
+            println!("{}", Book::write_line(&game.board));
+        } else {
+            for opening in Opening::starts() {
+                println!("{}", Book::write_line(&opening.board()));
+            }
+        }
+        return;
+    }
+    let mut console = Console::from_args(&args).unwrap();
+    let mut last_turn = None;
+    if args.len() > 2 && args[0] == "replay" {
+        last_turn = args[2].parse::<i32>().ok();
+    }
+    let naive = args.iter().any(|arg| arg == "naive");
+    let book = if args.iter().any(|arg| arg == "nobook") {
+        Book::default()
+    } else {
+        Book::builtin()
+    };
+    let option = |name: &str| {
+        let i = args.iter().position(|arg| arg == name)?;
+        args.get(i + 1)?.parse::<u64>().ok()
+    };
+    let mut beam = Beam::default();
+    if let Some(width) = option("--width") {
+        beam.width = width as usize;
+    }
+    if let Some(days) = option("--days") {
+        beam.days = days as i32;
+    }
+    if let Some(budget) = option("--budget") {
+        beam.budget = Duration::from_millis(budget);
+    }
+
+    // Initialize game
+    let mut game: Game = Game::new(&mut console).unwrap_or_else(|e| e.exit());
+    let mut turn = 0;
+
+    // game loop
+    while game.update(&mut console).unwrap_or_else(|e| e.exit()) {
+        // game.board.print_dbg();
+
+        // Write an action using println!("message...");
+        // To debug: eprintln!("Debug message...");
+
+        // GROW cellIdx | SEED sourceIdx targetIdx | COMPLETE cellIdx | WAIT <message>
+        if turn == 0 {
+            game.open_book(&book);
+        }
+        if game.book_move(&mut console).is_none() {
+            if naive {
+                game.naive_move(&mut console);
+            } else {
+                game.search_move(&beam, &mut console);
+            }
+        }
+        turn += 1;
+        if last_turn == Some(turn) {
+            break;
+        }
+
+        // RESET STATE
+        game.reset();
+    }
+}
@@ -0,0 +1,28 @@
+//! Legal actions worked out from the state alone, for either player, so
+//! the opponent's replies can be searched too.
+//!
+//! They come in the order the arena lists them: WAIT, then for every tree
+//! by cell index its COMPLETE, GROW and SEED targets by cell index.
+
+use crate::simulator::State;
+use crate::{Action, ActionKind};
+
+impl State {
+    /// Nothing once the player is waiting for the next day
+    pub fn legal_actions(&self, player: usize) -> Vec<Action> {
+        let mut candidates = vec![Action::new(ActionKind::Wait, -1, -1)];
+        for tree in self.trees(player) {
+            let index = tree.cell_index;
+            candidates.push(Action::new(ActionKind::Complete, index, -1));
+            candidates.push(Action::new(ActionKind::Grow, index, -1));
+            let targets = self.board.seed_targets(index as usize, tree.size);
+            candidates.extend(
+                targets
+                    .into_iter()
+                    .map(|t| Action::new(ActionKind::Seed, index, t as i32)),
+            );
+        }
+        candidates.retain(|action| self.cost(player, action).is_some());
+        candidates
+    }
+}
@@ -0,0 +1,173 @@
+//! Beam search over our own actions, within the day and across the days.
+//!
+//! The opponent is expected to wait, its trees only shade ours. A day is
+//! searched action by action until the sequences wait, the best `width`
+//! of them go on to the next day. Sequences are compared on their score
+//! and sun, the sun their trees will gather and what completing the trees
+//! will bring.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::simulator::{State, GROW_BASE_COST, RICHNESS_BONUS};
+use crate::{Action, ActionKind, GAME_LENGTH, TREE_LIFECYCLE_COST};
+
+/// Nutrients the opponent and us are expected to take every day
+const NUTRIENT_DECAY: i32 = 1;
+/// Percentage of the sun of the board as it is that counts, more than all
+/// of it as the trees keep growing
+const SUN_WEIGHT: i32 = 150;
+/// Percentage of its completion that a tree still to grow is worth
+const GROWING_WEIGHT: i32 = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Beam {
+    /// Sequences kept after every action
+    pub width: usize,
+    /// Days searched, the current one included
+    pub days: i32,
+    /// The search stops there and plays the best sequence found
+    pub budget: Duration,
+}
+
+impl Default for Beam {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            days: 2,
+            budget: Duration::from_millis(50),
+        }
+    }
+}
+
+struct Node {
+    state: State,
+    /// Action of the current turn leading to this node
+    first: Option<Action>,
+    value: i32,
+}
+
+/// Our points and sun counted in sun, 3 sun being a point at the end of the
+/// game, when only the final score is left. Completions are valued on the nutrients at the root of the search,
+/// so completing a tree doesn't make the others look worse.
+fn evaluate(state: &State, root_nutrients: i32) -> i32 {
+    if state.day >= GAME_LENGTH {
+        return 3 * state.final_score(0).0;
+    }
+    let player = &state.players[0];
+    let mut value = 3 * player.score + player.sun;
+    let days = state.board.project_sun(state.day, GAME_LENGTH);
+    value += days.iter().map(|sun| sun[0]).sum::<i32>() * SUN_WEIGHT / 100;
+    let days_left = GAME_LENGTH - state.day;
+    let nutrients = (root_nutrients - days_left * NUTRIENT_DECAY).max(0);
+    for tree in state.trees(0) {
+        // a grow a day, then the completion
+        let size = tree.size as usize;
+        if days_left < 4 - tree.size {
+            continue;
+        }
+        let richness = state.board.board[tree.cell_index as usize].richness as usize;
+        let to_grow = GROW_BASE_COST[size..].iter().sum::<i32>();
+        let complete = 3 * (nutrients + RICHNESS_BONUS[richness]) - TREE_LIFECYCLE_COST - to_grow;
+        value += if size == 3 {
+            complete
+        } else {
+            complete.max(0) * GROWING_WEIGHT / 100
+        };
+    }
+    value
+}
+
+/// Our legal actions without the ones not worth a look: trees that could
+/// not be completed before the end aren't grown or planted, no seed while
+/// one is already waiting to grow and one source per target, the smallest
+/// tree so the big ones stay free to grow
+fn candidates(state: &State) -> Vec<Action> {
+    let days_left = GAME_LENGTH - state.day;
+    let planted = state.count(0, 0) > 0;
+    let size = |action: &Action| state.board.get_tree_size(action.cell_index as usize);
+    let mut actions = state.legal_actions(0);
+    let mut sources = BTreeMap::new();
+    for action in actions.iter().filter(|a| a.kind == ActionKind::Seed) {
+        let source = sources
+            .entry(action.target_index)
+            .or_insert(action.cell_index);
+        if size(action) < state.board.get_tree_size(*source as usize) {
+            *source = action.cell_index;
+        }
+    }
+    actions.retain(|action| match action.kind {
+        // a grow a day up to size 3, then a day to complete
+        ActionKind::Grow => days_left >= 4 - size(action),
+        ActionKind::Seed => {
+            days_left > 4 && !planted && sources[&action.target_index] == action.cell_index
+        }
+        _ => true,
+    });
+    actions
+}
+
+impl Beam {
+    /// Our best action on `root`, WAIT when there is nothing else
+    pub fn search(&self, root: &State) -> Action {
+        let start = Instant::now();
+        let wait = Action::new(ActionKind::Wait, -1, -1);
+        let last_day = (root.day + self.days).min(GAME_LENGTH);
+        let nutrients = root.nutrients;
+        let mut root = root.clone();
+        root.players[1].waiting = true;
+        let mut beam = vec![Node {
+            value: evaluate(&root, nutrients),
+            state: root,
+            first: None,
+        }];
+        let mut best = None;
+        while beam[0].state.day < last_day {
+            let day = beam[0].state.day;
+            let mut done = Vec::new();
+            let mut open = beam;
+            while !open.is_empty() {
+                let mut children = Vec::new();
+                for node in open.iter() {
+                    for action in candidates(&node.state) {
+                        let mut state = node.state.clone();
+                        state.play([&action, &wait]);
+                        let child = Node {
+                            value: evaluate(&state, nutrients),
+                            first: node.first.clone().or(Some(action)),
+                            state,
+                        };
+                        if child.state.day > day {
+                            done.push(child);
+                        } else {
+                            children.push(child);
+                        }
+                    }
+                }
+                if start.elapsed() > self.budget {
+                    // the last full day, or the best of what is left
+                    done.extend(children);
+                    done.extend(open);
+                    return best.unwrap_or_else(|| Self::first(&mut done, &wait));
+                }
+                children.sort_by_key(|node| Reverse(node.value));
+                children.truncate(self.width);
+                open = children;
+            }
+            done.sort_by_key(|node| Reverse(node.value));
+            done.truncate(self.width);
+            best = done[0].first.clone();
+            beam = done;
+        }
+        best.unwrap_or(wait)
+    }
+
+    fn first(nodes: &mut [Node], wait: &Action) -> Action {
+        nodes.sort_by_key(|node| Reverse(node.value));
+        nodes
+            .iter()
+            .find_map(|node| node.first.clone())
+            .unwrap_or_else(|| wait.clone())
+    }
+}
@@ -0,0 +1,192 @@
+//! Forward model of Photosynthesis on the bot's `Board`.
+//!
+//! Player 0 is us and player 1 the opponent, a tree belongs to player 0 when
+//! `is_mine`. The actions of both players are applied at once, the day ends
+//! once both are waiting and the sun moves to the next of its 6 directions.
+
+use crate::{
+    Action, ActionKind, Board, Game, Player, Tree, FOREST_INITIAL_NUTRIENT, GAME_LENGTH,
+    POINTS_PER_3SUN, TREE_LIFECYCLE_COST,
+};
+
+pub const GROW_BASE_COST: [i32; 3] = [1, 3, 7];
+/// Points added to the nutrients for completing a tree, by richness
+pub const RICHNESS_BONUS: [i32; 4] = [0, 0, 2, 4];
+
+pub(crate) fn owner(tree: &Tree) -> usize {
+    if tree.is_mine {
+        0
+    } else {
+        1
+    }
+}
+
+#[derive(Clone)]
+pub struct State {
+    pub day: i32,
+    pub nutrients: i32,
+    pub board: Board,
+    pub players: [Player; 2],
+}
+
+impl State {
+    /// State of the turn the game was just updated to
+    pub fn new(game: &Game) -> State {
+        State {
+            day: game.day,
+            nutrients: game.nutrients,
+            board: game.board.clone(),
+            players: [game.me.clone(), game.opponent.clone()],
+        }
+    }
+
+    /// Start of the game on `board`, the trees already planted and the sun
+    /// of day 0 gathered
+    pub fn start(board: Board) -> State {
+        let mut state = State {
+            day: 0,
+            nutrients: FOREST_INITIAL_NUTRIENT,
+            board,
+            players: [Player::new(), Player::new()],
+        };
+        state.gather_sun();
+        state
+    }
+
+    pub fn trees(&self, player: usize) -> impl Iterator<Item = &Tree> {
+        self.board
+            .board
+            .iter()
+            .filter_map(|cell| cell.tree.as_ref())
+            .filter(move |tree| owner(tree) == player)
+    }
+
+    pub fn count(&self, player: usize, size: i32) -> i32 {
+        self.trees(player).filter(|t| t.size == size).count() as i32
+    }
+
+    /// Sun the action costs the player, `None` when it can't be played
+    pub fn cost(&self, player: usize, action: &Action) -> Option<i32> {
+        if self.players[player].waiting {
+            return None;
+        }
+        if action.kind == ActionKind::Wait {
+            return Some(0);
+        }
+        let tree = self
+            .board
+            .board
+            .get(action.cell_index as usize)?
+            .tree
+            .as_ref()
+            .filter(|t| owner(t) == player && !t.is_dormant)?;
+        let cost = match action.kind {
+            ActionKind::Grow if tree.size < 3 => {
+                GROW_BASE_COST[tree.size as usize] + self.count(player, tree.size + 1)
+            }
+            ActionKind::Complete if tree.size == 3 => TREE_LIFECYCLE_COST,
+            ActionKind::Seed if tree.size > 0 => {
+                let target = self.board.board.get(action.target_index as usize)?;
+                let distance = self
+                    .board
+                    .layout
+                    .distance(action.cell_index as usize, action.target_index as usize);
+                if target.richness == 0 || target.tree.is_some() || distance > tree.size {
+                    return None;
+                }
+                self.count(player, 0)
+            }
+            _ => return None,
+        };
+        Some(cost).filter(|&c| c <= self.players[player].sun)
+    }
+
+    /// Plays our action and the opponent's. An action that can't be played
+    /// counts as a WAIT, seeds both players send to the same cell are not
+    /// planted and their sun is given back.
+    pub fn play(&mut self, actions: [&Action; 2]) {
+        let costs = [0, 1].map(|p| self.cost(p, actions[p]));
+        let clash = actions[0].kind == ActionKind::Seed
+            && actions[1].kind == ActionKind::Seed
+            && costs.iter().all(Option::is_some)
+            && actions[0].target_index == actions[1].target_index;
+        let mut completed = 0;
+        for (player, action) in actions.into_iter().enumerate() {
+            if self.players[player].waiting {
+                continue;
+            }
+            let Some(cost) = costs[player] else {
+                self.players[player].waiting = true;
+                continue;
+            };
+            let index = action.cell_index as usize;
+            match action.kind {
+                ActionKind::Grow => {
+                    let tree = self.board.board[index].tree.as_mut().unwrap();
+                    tree.size += 1;
+                    tree.is_dormant = true;
+                }
+                ActionKind::Seed => {
+                    self.board.board[index].tree.as_mut().unwrap().is_dormant = true;
+                    if clash {
+                        continue;
+                    }
+                    self.board.update_tree(Tree {
+                        cell_index: action.target_index,
+                        size: 0,
+                        is_mine: player == 0,
+                        is_dormant: true,
+                    });
+                }
+                ActionKind::Complete => {
+                    let richness = self.board.board[index].richness as usize;
+                    self.players[player].score += self.nutrients + RICHNESS_BONUS[richness];
+                    self.board.board[index].reset_tree();
+                    completed += 1;
+                }
+                ActionKind::Wait => self.players[player].waiting = true,
+            }
+            self.players[player].sun -= cost;
+        }
+        self.nutrients = (self.nutrients - completed).max(0);
+        if self.players.iter().all(|p| p.waiting) {
+            self.next_day();
+        }
+    }
+
+    fn next_day(&mut self) {
+        self.day += 1;
+        if self.is_over() {
+            return;
+        }
+        for player in self.players.iter_mut() {
+            player.waiting = false;
+        }
+        for cell in self.board.board.iter_mut() {
+            if let Some(tree) = cell.tree.as_mut() {
+                tree.is_dormant = false;
+            }
+        }
+        self.gather_sun();
+    }
+
+    fn gather_sun(&mut self) {
+        let sun = self.board.sun_points(self.day);
+        for (player, sun) in self.players.iter_mut().zip(sun) {
+            player.sun += sun;
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.day >= GAME_LENGTH
+    }
+
+    /// Points with the leftover sun, and the number of trees breaking ties
+    pub fn final_score(&self, player: usize) -> (i32, usize) {
+        let p = &self.players[player];
+        (
+            p.score + p.sun / 3 * POINTS_PER_3SUN,
+            self.trees(player).count(),
+        )
+    }
+}
@@ -0,0 +1,77 @@
+//! Sun the trees will collect over the next days.
+//!
+//! The sun shines towards direction `day % 6`, every tree shades the cells
+//! behind it up to its size away and a tree in a shadow only loses its sun
+//! when it is not bigger than the tree casting it. Projections keep the
+//! board as it is, ours first and the opponent's second.
+
+use crate::simulator::owner;
+use crate::{Action, ActionKind, Board, Tree, GAME_LENGTH};
+
+impl Board {
+    /// Size of the biggest tree shading every cell, the sun shining towards
+    /// `direction`
+    pub fn shadow_sizes(&self, direction: usize) -> Vec<i32> {
+        let mut shadows = vec![0; self.board.len()];
+        for cell in self.board.iter() {
+            let Some(tree) = cell.tree.as_ref() else {
+                continue;
+            };
+            for i in self.layout.line(cell.index as usize, direction, tree.size) {
+                shadows[i] = shadows[i].max(tree.size);
+            }
+        }
+        shadows
+    }
+
+    /// Sun each player gathers at the start of `day`
+    pub fn sun_points(&self, day: i32) -> [i32; 2] {
+        let shadows = self.shadow_sizes(day as usize % 6);
+        let mut sun = [0, 0];
+        for cell in self.board.iter() {
+            let Some(tree) = cell.tree.as_ref() else {
+                continue;
+            };
+            if tree.size > shadows[cell.index as usize] {
+                sun[owner(tree)] += tree.size;
+            }
+        }
+        sun
+    }
+
+    /// Sun of each of the `horizon` days after `day`, none past the end of
+    /// the game
+    pub fn project_sun(&self, day: i32, horizon: i32) -> Vec<[i32; 2]> {
+        let last = (day + horizon).min(GAME_LENGTH - 1);
+        (day + 1..=last).map(|d| self.sun_points(d)).collect()
+    }
+
+    /// Sun both players win or lose over the horizon once we play `action`.
+    /// A seed counts as the size 1 tree it grows into.
+    pub fn marginal_sun(&self, action: &Action, day: i32, horizon: i32) -> [i32; 2] {
+        let mut after = self.clone();
+        let index = action.cell_index as usize;
+        match action.kind {
+            ActionKind::Grow => match after.board.get_mut(index).and_then(|c| c.tree.as_mut()) {
+                Some(tree) if tree.size < 3 => tree.size += 1,
+                _ => return [0, 0],
+            },
+            ActionKind::Seed => after.update_tree(Tree {
+                cell_index: action.target_index,
+                size: 1,
+                is_mine: true,
+                is_dormant: true,
+            }),
+            ActionKind::Complete => after.board[index].reset_tree(),
+            ActionKind::Wait => return [0, 0],
+        }
+        let before = self.project_sun(day, horizon);
+        let after = after.project_sun(day, horizon);
+        let mut sun = [0, 0];
+        for (b, a) in before.iter().zip(after.iter()) {
+            sun[0] += a[0] - b[0];
+            sun[1] += a[1] - b[1];
+        }
+        sun
+    }
+}
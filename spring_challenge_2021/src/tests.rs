@@ -0,0 +1,461 @@
+use crate::book::BOOK_DAYS;
+use crate::hex::{Hex, Layout};
+use crate::simulator::State;
+use crate::*;
+
+#[test]
+fn tree_line() {
+    let mut words = Words::new(42, "21  2 1 0\r\n");
+    let tree = Tree::from_line(&mut words).unwrap();
+    assert_eq!((tree.cell_index, tree.size), (21, 2));
+    assert!(tree.is_mine && !tree.is_dormant);
+    let mut words = Words::new(43, "21 2 yes 0");
+    let error = Tree::from_line(&mut words).unwrap_err();
+    assert_eq!((error.field, error.line_number), ("is_mine", 43));
+    assert_eq!(error.line, "21 2 yes 0");
+    let mut words = Words::new(44, "21 2");
+    assert_eq!(Tree::from_line(&mut words).unwrap_err().field, "is_mine");
+}
+
+#[test]
+fn action_line() {
+    let mut words = Words::new(1, "SEED 20 8\n");
+    let action = Action::from_line(&mut words).unwrap();
+    assert_eq!(action.action_string, "SEED 20 8");
+    assert_eq!(action.kind, ActionKind::Seed);
+    assert_eq!((action.cell_index, action.target_index), (20, 8));
+    let mut words = Words::new(2, "WAIT");
+    assert_eq!(Action::from_line(&mut words).unwrap().cell_index, -1);
+    let mut words = Words::new(3, "CUT 4");
+    assert_eq!(Action::from_line(&mut words).unwrap_err().field, "command");
+    assert_eq!(
+        Action::new(ActionKind::Seed, 20, 8).action_string,
+        "SEED 20 8"
+    );
+    assert_eq!(Action::new(ActionKind::Grow, 3, -1).action_string, "GROW 3");
+}
+
+/// Opening of a local match, the bot has to keep making the same decisions
+/// out of the same legal actions as the referee
+#[test]
+fn replay_opening() {
+    let mut console = Console::replay(include_str!("../replays/opening.txt"));
+    let mut game = Game::new(&mut console).unwrap();
+    assert_eq!(game.board.board[20].neighbours, [-1, -1, 21, 8, 7, 19]);
+    let mut turns = 0;
+    while game.update(&mut console).unwrap() {
+        turns += 1;
+        let no_mismatch = (Vec::new(), Vec::new());
+        assert_eq!(game.action_mismatch(), no_mismatch, "turn {}", turns);
+        assert!(game.naive_move(&mut console), "turn {}", turns);
+        game.reset();
+    }
+    assert_eq!(turns, 20);
+}
+
+/// Opening played by the search, unbounded in time so a debug build makes
+/// the same decisions
+#[test]
+fn replay_search() {
+    let mut console = Console::replay(include_str!("../replays/search.txt"));
+    let mut game = Game::new(&mut console).unwrap();
+    let beam = Beam {
+        budget: std::time::Duration::from_secs(60),
+        ..Beam::default()
+    };
+    let mut turns = 0;
+    while game.update(&mut console).unwrap() {
+        turns += 1;
+        let no_mismatch = (Vec::new(), Vec::new());
+        assert_eq!(game.action_mismatch(), no_mismatch, "turn {}", turns);
+        assert!(game.search_move(&beam, &mut console), "turn {}", turns);
+        game.reset();
+    }
+    assert_eq!(turns, 20);
+}
+
+#[test]
+fn hex_spiral() {
+    let layout = Layout::new();
+    assert_eq!(layout.len(), 37);
+    assert_eq!(layout.hex(0), Hex::ORIGIN);
+    assert_eq!(layout.hex(1), Hex::new(1, -1, 0));
+    assert_eq!(layout.hex(19), Hex::new(3, -3, 0));
+    for i in 0..layout.len() {
+        assert_eq!(layout.index(layout.hex(i)), Some(i));
+    }
+    assert_eq!(layout.index(Hex::new(4, -4, 0)), None);
+    let rings = (0..4)
+        .map(|r| Hex::ORIGIN.ring(r).len())
+        .collect::<Vec<_>>();
+    assert_eq!(rings, [1, 6, 12, 18]);
+    assert_eq!(layout.distance(0, 36), 3);
+    assert_eq!(layout.distance(19, 28), 6);
+}
+
+#[test]
+fn hex_lines_and_ranges() {
+    let layout = Layout::new();
+    assert_eq!(layout.line(0, 0, 3).collect::<Vec<_>>(), [1, 7, 19]);
+    assert_eq!(layout.line(19, 0, 3).count(), 0);
+    assert_eq!(layout.line(7, 3, 10).collect::<Vec<_>>(), [1, 0, 4, 13, 28]);
+    assert_eq!(layout.within(0, 1).collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6]);
+    assert_eq!(layout.within(0, 3).count(), 36);
+    assert_eq!(layout.within(19, 1).count(), 3);
+}
+
+/// Board lines as the referee sends them, from the hex layout
+fn board_input(richness: impl Fn(usize) -> i32) -> String {
+    let layout = Layout::new();
+    let mut input = format!("< {}\n", layout.len());
+    for i in 0..layout.len() {
+        let neighbours = (0..6).map(|d| layout.neighbour(i, d).map_or(-1, |n| n as i32));
+        let neighbours = neighbours.map(|n| n.to_string()).collect::<Vec<_>>();
+        input += &format!("< {} {} {}\n", i, richness(i), neighbours.join(" "));
+    }
+    input
+}
+
+#[test]
+fn hex_layout_matches_input() {
+    let mut console = Console::replay(include_str!("../replays/opening.txt"));
+    let game = Game::new(&mut console).unwrap();
+    for cell in game.board.board.iter() {
+        let hex = game.board.layout.hex(cell.index as usize);
+        for (d, &n) in cell.neighbours.iter().enumerate() {
+            let index = game.board.layout.index(hex.neighbour(d));
+            assert_eq!(index.map_or(-1, |i| i as i32), n, "cell {}", cell.index);
+        }
+    }
+    let mut console = Console::replay(&board_input(|_| 1));
+    assert!(Game::new(&mut console).is_ok());
+}
+
+#[test]
+fn hex_layout_mismatch() {
+    let input = board_input(|_| 1).replacen("< 0 1 1 ", "< 0 1 2 ", 1);
+    let mut console = Console::replay(&input);
+    let error = Game::new(&mut console).err().unwrap();
+    assert_eq!(error.field, "neighbours");
+    assert_eq!(error.line, "0 1 2 2 3 4 5 6");
+    assert!(matches!(error.kind, ErrorKind::Invalid(_)));
+    let input = board_input(|_| 1).replacen("< 1 1 ", "< 2 1 ", 1);
+    let mut console = Console::replay(&input);
+    assert_eq!(Game::new(&mut console).err().unwrap().field, "index");
+}
+
+#[test]
+fn shadows_and_seeds() {
+    let mut console = Console::replay(&board_input(|i| (i != 4) as i32));
+    let mut game = Game::new(&mut console).unwrap();
+    let tree = |cell_index, size, is_mine| Tree {
+        cell_index,
+        size,
+        is_mine,
+        is_dormant: false,
+    };
+    game.board.update_tree(tree(1, 1, false));
+    game.board.update_tree(tree(7, 2, true));
+    // a tree only shades the trees it is at least as big as
+    assert_eq!(game.board.shadow_sizes(0)[7], 1);
+    assert_eq!(game.board.shadow_sizes(3)[0..=2], [2, 2, 0]);
+    assert_eq!(game.board.sun_points(0), [2, 1]);
+    assert_eq!(game.board.sun_points(3), [2, 0]);
+    // unusable cells and trees can't be seeded
+    assert_eq!(game.board.seed_targets(0, 1), [2, 3, 5, 6]);
+}
+
+#[test]
+fn sun_projection() {
+    let mut console = Console::replay(&board_input(|_| 1));
+    let mut game = Game::new(&mut console).unwrap();
+    game.board.update_tree(Tree {
+        cell_index: 1,
+        size: 1,
+        is_mine: false,
+        is_dormant: false,
+    });
+    game.board.update_tree(Tree {
+        cell_index: 7,
+        size: 2,
+        is_mine: true,
+        is_dormant: false,
+    });
+    // days 1 to 6, the opponent's tree is in our shadow on day 3
+    let days = game.board.project_sun(0, 6);
+    assert_eq!(days.len(), 6);
+    assert_eq!(days[2], [2, 0]);
+    assert_eq!(days.iter().map(|d| d[1]).sum::<i32>(), 5);
+    assert_eq!(game.board.project_sun(GAME_LENGTH - 2, 6).len(), 1);
+    let board = &game.board;
+    assert_eq!(board.marginal_sun(&action("GROW 7"), 0, 6), [6, 0]);
+    assert_eq!(board.marginal_sun(&action("COMPLETE 7"), 0, 6), [-12, 1]);
+    // the seed is shaded on day 2 and shades the opponent on day 5
+    assert_eq!(board.marginal_sun(&action("SEED 7 2"), 0, 6), [5, -1]);
+    assert_eq!(board.marginal_sun(&action("WAIT"), 0, 6), [0, 0]);
+}
+
+fn action(line: &str) -> Action {
+    Action::from_line(&mut Words::new(0, line)).unwrap()
+}
+
+/// Start of a game on a board of richness 3 in the middle down to 1 on
+/// the outer ring, with the trees `(cell, size, is_mine)`
+fn start(trees: &[(i32, i32, bool)]) -> State {
+    let layout = Layout::new();
+    let richness = |i| 3 - (layout.distance(0, i) - 1).max(0);
+    let mut console = Console::replay(&board_input(richness));
+    let mut game = Game::new(&mut console).unwrap();
+    for &(cell_index, size, is_mine) in trees {
+        game.board.update_tree(Tree {
+            cell_index,
+            size,
+            is_mine,
+            is_dormant: false,
+        });
+    }
+    State::start(game.board)
+}
+
+#[test]
+fn sim_costs() {
+    let mut state = start(&[(1, 1, true), (2, 2, true), (3, 2, true), (4, 3, false)]);
+    state.players[0].sun = 5;
+    assert_eq!(state.cost(0, &action("GROW 1")), Some(3 + 2));
+    assert_eq!(state.cost(0, &action("GROW 2")), None);
+    assert_eq!(state.cost(0, &action("SEED 1 0")), Some(0));
+    assert_eq!(state.cost(0, &action("SEED 1 19")), None);
+    assert_eq!(state.cost(0, &action("SEED 1 4")), None);
+    assert_eq!(state.cost(0, &action("COMPLETE 4")), None);
+    assert_eq!(state.cost(0, &action("COMPLETE 2")), None);
+    assert_eq!(state.cost(0, &action("WAIT")), Some(0));
+    // seeding grows the cost of the next seed, the source is now dormant
+    state.play([&action("SEED 2 0"), &action("WAIT")]);
+    assert_eq!(state.count(0, 0), 1);
+    assert_eq!(state.cost(0, &action("SEED 2 8")), None);
+    assert_eq!(state.cost(0, &action("SEED 3 10")), Some(1));
+}
+
+#[test]
+fn sim_sun_turns_with_the_days() {
+    // the sun shines east on day 0, the small tree east of the center is
+    // in the shadow
+    let mut state = start(&[(0, 2, true), (1, 1, false)]);
+    assert_eq!((state.players[0].sun, state.players[1].sun), (2, 0));
+    state.play([&action("WAIT"), &action("WAIT")]);
+    assert_eq!(state.day, 1);
+    assert_eq!((state.players[0].sun, state.players[1].sun), (4, 1));
+    assert!(!state.players[0].waiting && !state.players[1].waiting);
+    // growing beyond the sun left is a WAIT, both waiting ends the day
+    state.play([&action("GROW 0"), &action("GROW 1")]);
+    assert_eq!(state.day, 2);
+    assert_eq!(state.board.get_tree_size(0), 2);
+    assert_eq!((state.players[0].sun, state.players[1].sun), (6, 2));
+    // a waiting player sits out the rest of the day
+    state.players[0].sun = 10;
+    state.players[1].sun = 5;
+    state.play([&action("GROW 0"), &action("WAIT")]);
+    state.play([&action("WAIT"), &action("GROW 1")]);
+    assert_eq!(state.day, 3);
+    assert_eq!(state.board.get_tree_size(0), 3);
+    assert_eq!(state.board.get_tree_size(1), 1);
+    assert_eq!(
+        (state.players[0].sun, state.players[1].sun),
+        (10 - 7 + 3, 5 + 1)
+    );
+}
+
+#[test]
+fn sim_complete_and_nutrients() {
+    let mut state = start(&[(0, 3, true), (19, 3, false)]);
+    state.players[0].sun = 4;
+    state.players[1].sun = 4;
+    state.play([&action("COMPLETE 0"), &action("COMPLETE 19")]);
+    assert_eq!(
+        (state.players[0].score, state.players[1].score),
+        (20 + 4, 20)
+    );
+    assert_eq!(state.nutrients, 18);
+    assert_eq!(state.players[0].sun, 0);
+    assert!(state.board.board[0].tree.is_none());
+}
+
+#[test]
+fn sim_seeds_on_the_same_cell() {
+    let mut state = start(&[(1, 1, true), (3, 1, false), (4, 1, false)]);
+    state.play([&action("SEED 1 2"), &action("SEED 3 2")]);
+    assert!(state.board.board[2].tree.is_none());
+    assert!(state.board.board[1].tree.as_ref().unwrap().is_dormant);
+    assert!(state.board.board[3].tree.as_ref().unwrap().is_dormant);
+    // the seeds were free anyway, with one planted the next one costs
+    state.play([&action("WAIT"), &action("SEED 4 5")]);
+    assert_eq!(state.cost(1, &action("SEED 3 2")), None);
+    assert_eq!(state.board.get_tree_size(5), 0);
+}
+
+/// Starting from the board alone gives the state of the first turn, which
+/// the book lines are written from
+#[test]
+fn sim_start_is_the_first_turn() {
+    let mut console = Console::replay(include_str!("../replays/search.txt"));
+    let mut game = Game::new(&mut console).unwrap();
+    game.update(&mut console).unwrap();
+    let first = State::new(&game);
+    let start = State::start(game.board.clone());
+    assert_eq!((start.day, start.nutrients), (first.day, first.nutrients));
+    for (a, b) in start.players.iter().zip(first.players.iter()) {
+        assert_eq!((a.sun, a.score), (b.sun, b.score));
+    }
+}
+
+#[test]
+fn sim_game_end() {
+    let mut state = start(&[(0, 3, true), (19, 1, false)]);
+    state.day = GAME_LENGTH - 1;
+    state.players[0].sun = 7;
+    state.players[1].score = 2;
+    assert!(!state.is_over());
+    state.play([&action("WAIT"), &action("WAIT")]);
+    assert!(state.is_over());
+    assert_eq!(state.players[0].sun, 7);
+    assert_eq!(state.final_score(0), (2, 1));
+    assert_eq!(state.final_score(1), (2, 1));
+}
+
+#[test]
+fn legal_actions_from_the_state() {
+    let mut state = start(&[(1, 1, true), (7, 2, true), (4, 3, false)]);
+    state.board.board[2].richness = 0;
+    state.board.board[7].tree.as_mut().unwrap().is_dormant = true;
+    state.players[0].sun = 3;
+    state.players[1].sun = 4;
+    let strings = |player| {
+        state
+            .legal_actions(player)
+            .into_iter()
+            .map(|a| a.action_string)
+            .collect::<Vec<_>>()
+    };
+    // growing costs 3 + 1, the dormant tree does nothing, unusable cells
+    // and trees can't be seeded
+    assert_eq!(
+        strings(0),
+        ["WAIT", "SEED 1 0", "SEED 1 6", "SEED 1 8", "SEED 1 18"]
+    );
+    let theirs = strings(1);
+    assert_eq!(theirs[..2], ["WAIT", "COMPLETE 4"]);
+    assert!(!theirs.contains(&"GROW 4".to_string()));
+    let seeds = theirs.iter().filter(|a| a.starts_with("SEED 4 "));
+    assert_eq!(seeds.count(), state.board.seed_targets(4, 3).len());
+    state.players[1].waiting = true;
+    assert!(state.legal_actions(1).is_empty());
+}
+
+#[test]
+fn search_completes_when_it_pays() {
+    let beam = Beam::default();
+    // a tree in the sun is worth more than completing it early
+    let mut state = start(&[(0, 3, true), (22, 1, false)]);
+    state.day = 5;
+    state.players[0].sun = 20;
+    assert_ne!(beam.search(&state).kind, ActionKind::Complete);
+    // on the last day the richest cell goes first
+    let mut state = start(&[(0, 3, true), (19, 3, true), (22, 3, false)]);
+    state.day = GAME_LENGTH - 1;
+    state.players[0].sun = 4;
+    assert_eq!(beam.search(&state).action_string, "COMPLETE 0");
+    state.players[0].sun = 3;
+    assert_eq!(beam.search(&state).kind, ActionKind::Wait);
+}
+
+#[test]
+fn book_lines() {
+    let text = "\n24 34 / - / - / GROW 24, SEED 34 16\n1 2 / 8 14 / SEED 1 0\n";
+    let book = Book::parse(text).unwrap();
+    let opening = |trees: Vec<i32>, unusable: Vec<i32>| Opening { trees, unusable };
+    let line = book.line(&opening(vec![24, 34], vec![]));
+    assert_eq!(line.len(), 2);
+    assert!(line[0].is_empty());
+    let day: Vec<_> = line[1].iter().map(|a| a.action_string.as_str()).collect();
+    assert_eq!(day, ["GROW 24", "SEED 34 16"]);
+    assert_eq!(book.line(&opening(vec![1, 2], vec![8, 14])).len(), 1);
+    assert!(book.line(&opening(vec![1, 2], vec![])).is_empty());
+    // the line of the same trees without unusable cells
+    assert_eq!(book.line(&opening(vec![24, 34], vec![8, 14])).len(), 2);
+    assert!(book.line(&opening(vec![24, 35], vec![])).is_empty());
+    let error = Book::parse("24 34 / - / GROW 24\n24 34\n").unwrap_err();
+    assert_eq!((error.field, error.line_number), ("unusable cells", 2));
+    let error = Book::parse("24 34 / - / GROW x").unwrap_err();
+    assert_eq!((error.field, error.line_number), ("cell_index", 1));
+}
+
+/// Every start the server deals has a line, and a layout with unusable
+/// cells plays the line of its trees
+#[test]
+fn book_covers_server_starts() {
+    let book = Book::builtin();
+    let starts = Opening::starts();
+    assert_eq!(starts.len(), 18 * 17 / 2 - 9);
+    for opening in starts.iter() {
+        assert_eq!(
+            book.line(opening).len(),
+            BOOK_DAYS as usize,
+            "{:?}",
+            opening
+        );
+    }
+    // trees and unusable cells dealt like the official referee does, the
+    // unusable cells in opposite pairs
+    let opening = Opening {
+        trees: vec![20, 26],
+        unusable: vec![9, 15, 24, 33],
+    };
+    let line = book.line(&Opening::new(&opening.board()));
+    assert!(!line.is_empty());
+    let mut state = State::start(opening.board());
+    state.players[1].waiting = true;
+    let wait = action("WAIT");
+    let mut unplayable = None;
+    for day in line.iter() {
+        for next in day.iter() {
+            if state.cost(0, next).is_none() {
+                unplayable = Some(next.action_string.as_str());
+                break;
+            }
+            state.play([next, &wait]);
+        }
+        if unplayable.is_some() {
+            break;
+        }
+        state.play([&wait, &wait]);
+    }
+    // followed until it seeds an unusable cell
+    assert_eq!((state.day, unplayable), (3, Some("SEED 20 9")));
+}
+
+/// The opening of the search replay is in the book, a line is followed
+/// until one of its actions can't be played
+#[test]
+fn book_follows_its_line() {
+    let mut console = Console::replay(include_str!("../replays/search.txt"));
+    let mut game = Game::new(&mut console).unwrap();
+    game.update(&mut console).unwrap();
+    let opening = Opening::new(&game.board);
+    assert_eq!((opening.trees, opening.unusable), (vec![24, 34], vec![]));
+    assert!(!Book::builtin().line(&Opening::new(&game.board)).is_empty());
+    let text = "24 34 / - / - / - / GROW 34 / GROW 24, SEED 34 16 / GROW 16 / GROW 34, COMPLETE 9";
+    game.open_book(&Book::parse(text).unwrap());
+    let mut turns = 0;
+    loop {
+        turns += 1;
+        match game.book_move(&mut console) {
+            Some(matched) => assert!(matched, "turn {}", turns),
+            None => break,
+        }
+        game.reset();
+        assert!(game.update(&mut console).unwrap());
+    }
+    // there is no tree on 9 to complete on day 5
+    assert_eq!((game.day, turns), (5, 11));
+    assert!(game.line.is_empty());
+}
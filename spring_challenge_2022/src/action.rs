@@ -0,0 +1,97 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::{Hero, Vec2};
+
+/// Command for a single hero, printed only once all heroes have decided
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeroAction {
+    Wait {
+        message: String,
+    },
+    Move {
+        target: Vec2,
+        message: String,
+    },
+    Wind {
+        target: Vec2,
+        message: String,
+    },
+    Shield {
+        id: i32,
+        message: String,
+    },
+    Control {
+        id: i32,
+        target: Vec2,
+        message: String,
+    },
+}
+
+/// Entity a spell is cast on, as seen this turn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpellTarget {
+    pub pos: Vec2,
+    /// One of our own heroes, which can be shielded but not controlled
+    pub is_mine: bool,
+}
+
+impl HeroAction {
+    pub fn cost(&self) -> u32 {
+        match self {
+            HeroAction::Wait { .. } | HeroAction::Move { .. } => 0,
+            _ => Hero::SPELL_COST,
+        }
+    }
+
+    /// Entity the spell is cast on
+    pub fn target_id(&self) -> Option<i32> {
+        match self {
+            HeroAction::Shield { id, .. } | HeroAction::Control { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Checks the mana, the range and the target of the spell, `target` is
+    /// the entity from `target_id` if it is visible
+    pub fn is_valid(&self, hero: &Hero, mana: u32, target: Option<&SpellTarget>) -> bool {
+        if self.cost() > mana {
+            return false;
+        }
+        match self {
+            HeroAction::Wait { .. } | HeroAction::Move { .. } => true,
+            HeroAction::Wind { target, .. } => *target != hero.pos,
+            HeroAction::Shield { .. } => {
+                target.is_some_and(|t| t.pos.distance(&hero.pos) <= Hero::SHIELD_RANGE)
+            }
+            HeroAction::Control { .. } => target
+                .is_some_and(|t| !t.is_mine && t.pos.distance(&hero.pos) <= Hero::CONTROL_RANGE),
+        }
+    }
+}
+
+impl fmt::Display for HeroAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeroAction::Wait { message } => write!(f, "WAIT {}", message),
+            HeroAction::Move { target, message } => write!(f, "MOVE {} {}", target, message),
+            HeroAction::Wind { target, message } => {
+                write!(f, "SPELL WIND {} {}", target, message)
+            }
+            HeroAction::Shield { id, message } => write!(f, "SPELL SHIELD {} {}", id, message),
+            HeroAction::Control {
+                id,
+                target,
+                message,
+            } => write!(f, "SPELL CONTROL {} {} {}", id, target, message),
+        }
+    }
+}
+
+/// Emits the whole turn at once, one line per hero
+pub fn write_actions<W: Write>(out: &mut W, actions: &[HeroAction]) -> io::Result<()> {
+    for action in actions.iter() {
+        writeln!(out, "{}", action)?;
+    }
+    out.flush()
+}
@@ -0,0 +1,103 @@
+use crate::{Hero, Monster};
+
+/// Cost of letting a monster reach the base
+const LEAK: i32 = 1000;
+/// Unattended monsters cost more the sooner they arrive, starting this many
+/// turns ahead
+const HORIZON: i32 = 30;
+const UNATTENDED: i32 = 10;
+/// Only the most urgent monsters are considered
+const MAX_MONSTERS: usize = 8;
+
+/// Turn at which the monster dies when heroes start hitting it at the
+/// given intercept turns
+pub fn kill_time(intercepts: &[i32], hp: i32) -> i32 {
+    let Some(&first) = intercepts.iter().min() else {
+        return i32::MAX;
+    };
+    let mut t = first;
+    loop {
+        let dmg: i32 = intercepts.iter().map(|&i| (t - i).max(0) * Hero::DMG).sum();
+        if dmg >= hp {
+            return t;
+        }
+        t += 1;
+    }
+}
+
+/// Cost of one monster given the intercept turns of the heroes sent to it,
+/// `all` being the ones of every hero. Leaving alone a monster that not even
+/// all the heroes could kill in time is a leak, sending someone at least
+/// gets it pushed away.
+fn monster_cost(monster: &Monster, sent: &[i32], all: &[i32]) -> i32 {
+    if sent.is_empty() {
+        let urgency = (HORIZON - monster.eta).max(0) * UNATTENDED;
+        return if kill_time(all, monster.hp) > monster.eta {
+            LEAK + urgency
+        } else {
+            urgency
+        };
+    }
+    let k = kill_time(sent, monster.hp);
+    if k > monster.eta {
+        LEAK + k
+    } else {
+        k
+    }
+}
+
+fn total_cost(monsters: &[Monster], intercepts: &[Vec<i32>], choice: &[Option<usize>]) -> i32 {
+    let mut cost = 0;
+    for (j, m) in monsters.iter().enumerate() {
+        let sent = choice
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == Some(j))
+            .map(|(h, _)| intercepts[h][j])
+            .collect::<Vec<_>>();
+        let all = intercepts.iter().map(|h| h[j]).collect::<Vec<_>>();
+        cost += monster_cost(m, &sent, &all);
+    }
+    cost
+}
+
+fn search(
+    monsters: &[Monster],
+    intercepts: &[Vec<i32>],
+    choice: &mut Vec<Option<usize>>,
+    best: &mut (i32, Vec<Option<usize>>),
+) {
+    if choice.len() == intercepts.len() {
+        let cost = total_cost(monsters, intercepts, choice);
+        if cost < best.0 {
+            *best = (cost, choice.clone());
+        }
+        return;
+    }
+    for c in std::iter::once(None).chain((0..monsters.len()).map(Some)) {
+        choice.push(c);
+        search(monsters, intercepts, choice, best);
+        choice.pop();
+    }
+}
+
+/// Optimal monster (index into `monsters`) for each hero, trying every
+/// combination including several heroes on the same monster
+pub fn assign(heroes: &[Hero], monsters: &[Monster]) -> Vec<Option<usize>> {
+    let mut order = (0..monsters.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&j| (monsters[j].eta, monsters[j].id));
+    order.truncate(MAX_MONSTERS);
+    let considered = order.iter().map(|&j| monsters[j]).collect::<Vec<_>>();
+    let intercepts = heroes
+        .iter()
+        .map(|h| {
+            considered
+                .iter()
+                .map(|m| h.find_intercept(m).1)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut best = (i32::MAX, vec![None; heroes.len()]);
+    search(&considered, &intercepts, &mut Vec::new(), &mut best);
+    best.1.iter().map(|c| c.map(|j| order[j])).collect()
+}
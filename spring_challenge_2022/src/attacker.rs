@@ -0,0 +1,208 @@
+use crate::{Hero, HeroAction, Monster, Vec2};
+
+/// Spell the attacker casts on a single monster
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spell {
+    Control,
+    Wind,
+    Shield,
+}
+
+/// Sequence of spells on one monster, with the hero's action for each turn
+/// of it, starting with this one
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub monster: i32,
+    pub spells: Vec<Spell>,
+    pub turns: i32,
+    pub mana: u32,
+    pub damage: f32,
+    pub actions: Vec<HeroAction>,
+    /// Where the monster is once the last spell is cast
+    pub end: Vec2,
+}
+
+impl Plan {
+    pub fn damage_per_mana(&self) -> f32 {
+        if self.mana == 0 {
+            0.0
+        } else {
+            self.damage / self.mana as f32
+        }
+    }
+}
+
+/// Scores spell combos pushing monsters into the enemy base
+pub struct Planner<'a> {
+    pub hero: &'a Hero,
+    pub enemy_base: Vec2,
+    pub enemy_heroes: &'a [Vec2],
+    pub mana: u32,
+    pub income: f32,
+}
+
+impl<'a> Planner<'a> {
+    const SEQUENCES: [&'static [Spell]; 7] = [
+        &[Spell::Control],
+        &[Spell::Wind],
+        &[Spell::Shield],
+        &[Spell::Control, Spell::Wind],
+        &[Spell::Control, Spell::Shield],
+        &[Spell::Wind, Spell::Shield],
+        &[Spell::Control, Spell::Wind, Spell::Shield],
+    ];
+    /// Longest plan looked at, in turns
+    pub const MAX_TURNS: i32 = 6;
+    const SHIELD_ETA: i32 = 15;
+    /// Chance that an unshielded monster within reach of a defender still
+    /// makes it to the base
+    const UNSHIELDED_SURVIVAL: f32 = 0.25;
+
+    /// Chance between 0 and 1 that the monster damages the enemy base
+    pub fn expected_damage(&self, m: &Monster) -> f32 {
+        if m.target != Some(self.enemy_base) || m.eta == i32::MAX {
+            return 0.0;
+        }
+        // nobody seen, expect a defender sitting at the base
+        let defenders = if self.enemy_heroes.is_empty() {
+            std::slice::from_ref(&self.enemy_base)
+        } else {
+            self.enemy_heroes
+        };
+        let mut survival: f32 = 1.0;
+        for pos in defenders.iter() {
+            let (_, reach) = m.intercept(pos, Hero::ATTACK_RANGE);
+            let kill = reach + (m.hp + Hero::DMG - 1) / Hero::DMG;
+            if kill <= m.eta {
+                return 0.0;
+            }
+            let (_, spell_reach) = m.intercept(pos, Hero::WIND_RANGE);
+            if spell_reach < m.eta && m.shield <= spell_reach {
+                survival = survival.min(Self::UNSHIELDED_SURVIVAL);
+            }
+        }
+        survival
+    }
+
+    /// Expected damage gained per mana by the given action, used to compare
+    /// other attack logic with the plans
+    pub fn score(&self, action: &HeroAction, monsters: &[Monster]) -> f32 {
+        let mut gain = 0.0;
+        for m in monsters.iter() {
+            let mut after = *m;
+            let hit = match action {
+                HeroAction::Wind { target, .. } => after.apply_wind(&self.hero.pos, target),
+                HeroAction::Control { id, target, .. } if *id == m.id => {
+                    after.apply_control(&self.hero.pos, target)
+                }
+                HeroAction::Shield { id, .. } if *id == m.id => after.apply_shield(&self.hero.pos),
+                _ => false,
+            };
+            if hit {
+                gain += self.expected_damage(&after) - self.expected_damage(m);
+            }
+        }
+        if action.cost() == 0 {
+            0.0
+        } else {
+            gain / action.cost() as f32
+        }
+    }
+
+    fn cast(&self, spell: Spell, m: &mut Monster, pos: &Vec2) -> bool {
+        match spell {
+            Spell::Control => {
+                m.target != Some(self.enemy_base) && m.apply_control(pos, &self.enemy_base)
+            }
+            Spell::Wind => m.apply_wind(pos, &self.enemy_base),
+            Spell::Shield => {
+                m.target == Some(self.enemy_base) && m.eta < Self::SHIELD_ETA && m.apply_shield(pos)
+            }
+        }
+    }
+
+    fn action(&self, spell: Spell, m: &Monster) -> HeroAction {
+        let yell = Some(format!("{:?}{}", spell, m.id));
+        match spell {
+            Spell::Control => self.hero.control(m.id, &self.enemy_base, yell),
+            Spell::Wind => self.hero.wind(&self.enemy_base, yell),
+            Spell::Shield => self.hero.shield(m.id, yell),
+        }
+    }
+
+    /// Plays the spells one per turn, moving the hero towards the monster
+    /// while it is out of range, within `horizon` turns
+    fn simulate(&self, monster: &Monster, spells: &[Spell], horizon: i32) -> Option<Plan> {
+        let mut m = *monster;
+        let mut pos = self.hero.pos;
+        let mut mana = self.mana as f32;
+        let mut actions = Vec::new();
+        let mut turns = 0;
+        for &spell in spells.iter() {
+            loop {
+                if turns >= horizon || m.despawned() {
+                    return None;
+                }
+                let mut next = m;
+                let cast = mana >= Hero::SPELL_COST as f32 && self.cast(spell, &mut next, &pos);
+                actions.push(if cast {
+                    self.action(spell, &m)
+                } else {
+                    self.hero.move_to(&m.pos, Some(format!("A{}", m.id)))
+                });
+                if !cast {
+                    let d = pos.distance(&m.pos);
+                    pos = if d <= Hero::SPEED {
+                        m.pos
+                    } else {
+                        pos + (m.pos - pos).normalize() * Hero::SPEED
+                    };
+                }
+                if cast {
+                    m = next;
+                }
+                m.simulate_move();
+                m.eta();
+                mana += self.income;
+                turns += 1;
+                if cast {
+                    mana -= Hero::SPELL_COST as f32;
+                    break;
+                }
+            }
+        }
+        let mana = spells.len() as u32 * Hero::SPELL_COST;
+        Some(Plan {
+            monster: monster.id,
+            spells: spells.to_vec(),
+            turns,
+            mana,
+            damage: self.expected_damage(&m) - self.expected_damage(monster),
+            actions,
+            end: m.pos,
+        })
+    }
+
+    /// Plan with the best expected damage per mana over all monsters, taking
+    /// at most `horizon` turns
+    pub fn best_plan(&self, monsters: &[Monster], horizon: i32) -> Option<Plan> {
+        let mut best: Option<Plan> = None;
+        for m in monsters.iter() {
+            for spells in Self::SEQUENCES.iter() {
+                let Some(plan) = self.simulate(m, spells, horizon) else {
+                    continue;
+                };
+                if plan.damage <= 0.0 {
+                    continue;
+                }
+                if best
+                    .as_ref()
+                    .is_none_or(|b| plan.damage_per_mana() > b.damage_per_mana())
+                {
+                    best = Some(plan);
+                }
+            }
+        }
+        best
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Source of the current time, as a duration since any fixed origin
+pub trait Time {
+    fn now(&self) -> Duration;
+}
+
+/// The real time, the tests use a fake one they move forward themselves
+#[derive(Debug, Clone, Copy)]
+pub struct Wall(Instant);
+
+impl Time for Wall {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Time left in the current turn, started as soon as the first input line of
+/// the turn has been read
+#[derive(Debug, Clone)]
+pub struct TurnClock<C: Time = Wall> {
+    time: C,
+    start: Duration,
+    turn: u32,
+    first_budget: Duration,
+    budget: Duration,
+    margin: Duration,
+    /// Turns that ate into the safety margin
+    late: u32,
+    /// Turns past the hard budget, which would have timed out
+    overruns: u32,
+    worst: Duration,
+    total: Duration,
+}
+
+impl TurnClock {
+    /// Turns of a whole match, the input doesn't end after the last one
+    pub const MAX_TURNS: u32 = cg_common::spring2022::MAX_TURNS;
+    pub const FIRST_BUDGET: Duration = Duration::from_millis(100);
+    pub const BUDGET: Duration = Duration::from_millis(50);
+    /// Kept for writing the output and scheduling hiccups
+    pub const MARGIN: Duration = Duration::from_millis(5);
+
+    pub fn new(first_budget: Duration, budget: Duration, margin: Duration) -> Self {
+        Self::with_time(Wall(Instant::now()), first_budget, budget, margin)
+    }
+}
+
+impl<C: Time> TurnClock<C> {
+    pub fn with_time(time: C, first_budget: Duration, budget: Duration, margin: Duration) -> Self {
+        Self {
+            start: time.now(),
+            time,
+            turn: 0,
+            first_budget,
+            budget,
+            margin,
+            late: 0,
+            overruns: 0,
+            worst: Duration::ZERO,
+            total: Duration::ZERO,
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.start = self.time.now();
+        self.turn += 1;
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn is_last_turn(&self) -> bool {
+        self.turn == TurnClock::MAX_TURNS
+    }
+
+    /// Hard limit of the current turn
+    pub fn budget(&self) -> Duration {
+        if self.turn <= 1 {
+            self.first_budget
+        } else {
+            self.budget
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.time.now().saturating_sub(self.start)
+    }
+
+    /// Time that can still be spent thinking, the margin excluded
+    pub fn remaining(&self) -> Duration {
+        self.budget()
+            .saturating_sub(self.margin)
+            .saturating_sub(self.elapsed())
+    }
+
+    pub fn should_stop(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Ends the turn once the output is written and records its duration
+    pub fn finish(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        if elapsed > self.budget() {
+            self.overruns += 1;
+        } else if elapsed > self.budget().saturating_sub(self.margin) {
+            self.late += 1;
+        }
+        self.worst = self.worst.max(elapsed);
+        self.total += elapsed;
+        elapsed
+    }
+
+    /// Iterative deepening: runs `step` with depth 1, 2, ... up to
+    /// `max_depth` while the clock allows and returns the result of the
+    /// deepest completed step. A depth is not started when the time left is
+    /// less than twice what the previous one took, as it would hardly finish.
+    pub fn deepen<T>(
+        &self,
+        max_depth: usize,
+        mut step: impl FnMut(usize) -> T,
+    ) -> Option<(T, usize)> {
+        let mut best = None;
+        let mut last = Duration::ZERO;
+        for depth in 1..=max_depth {
+            if self.should_stop() || self.remaining() < last * 2 {
+                break;
+            }
+            let now = self.time.now();
+            best = Some((step(depth), depth));
+            last = self.time.now().saturating_sub(now);
+        }
+        best
+    }
+}
+
+impl Default for TurnClock {
+    fn default() -> Self {
+        Self::new(Self::FIRST_BUDGET, Self::BUDGET, Self::MARGIN)
+    }
+}
+
+impl<C: Time> fmt::Display for TurnClock<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let average = if self.turn == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.turn
+        };
+        write!(
+            f,
+            "{} turns | average {:?} worst {:?} | {} in margin, {} overruns",
+            self.turn, average, self.worst, self.late, self.overruns
+        )
+    }
+}
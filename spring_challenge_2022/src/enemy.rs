@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use crate::{Hero, Monster, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Defender,
+    Attacker,
+}
+
+/// Last known state of an opponent hero
+#[derive(Debug, Clone, Copy)]
+pub struct EnemyHero {
+    pub pos: Vec2,
+    pub velocity: Vec2,
+    pub last_seen: u32,
+    pub role: Role,
+}
+
+impl EnemyHero {
+    /// Where the hero is expected next turn if it keeps moving
+    pub fn next_pos(&self) -> Vec2 {
+        self.pos + self.velocity
+    }
+}
+
+/// Remembers opponent heroes and predicts which spells they can cast on us
+#[derive(Debug, Clone, Default)]
+pub struct EnemyTracker {
+    turn: u32,
+    heroes: BTreeMap<i32, EnemyHero>,
+}
+
+impl EnemyTracker {
+    /// Heroes further than this from their own base are attacking us
+    const ATTACKER_DISTANCE: f32 = 8000.0;
+    /// Sightings older than this are too stale to predict spells from
+    const MAX_AGE: u32 = 2;
+
+    pub fn next_turn(&mut self) {
+        self.turn += 1;
+    }
+
+    /// `base` is the opponent's own base
+    pub fn observe(&mut self, id: i32, pos: Vec2, base: &Vec2) {
+        let velocity = match self.heroes.get(&id) {
+            Some(h) if h.last_seen + 1 == self.turn => pos - h.pos,
+            _ => Vec2::ZERO,
+        };
+        let role = if pos.distance(base) > Self::ATTACKER_DISTANCE {
+            Role::Attacker
+        } else {
+            Role::Defender
+        };
+        self.heroes.insert(
+            id,
+            EnemyHero {
+                pos,
+                velocity,
+                last_seen: self.turn,
+                role,
+            },
+        );
+    }
+
+    /// Heroes seen lately enough to still be around there
+    pub(crate) fn recent(&self) -> impl Iterator<Item = &EnemyHero> {
+        let turn = self.turn;
+        self.heroes
+            .values()
+            .filter(move |h| h.last_seen + Self::MAX_AGE >= turn)
+    }
+
+    /// Whether an opponent can cast a spell of `range` on something at `pos`
+    /// now or after moving one more turn
+    fn in_reach(&self, pos: &Vec2, next: &Vec2, range: f32) -> bool {
+        self.recent().any(|h| {
+            h.role == Role::Attacker
+                && (h.pos.distance(pos) <= range || h.next_pos().distance(next) <= range)
+        })
+    }
+
+    /// Monster likely to be winded or controlled by an opponent next turn
+    pub fn threatens(&self, monster: &Monster) -> bool {
+        let mut next = *monster;
+        next.simulate_move();
+        let range = Hero::WIND_RANGE.max(Hero::CONTROL_RANGE);
+        self.in_reach(&monster.pos, &next.pos, range)
+    }
+
+    /// Whether our hero at `pos` can be controlled next turn
+    pub fn can_control(&self, pos: &Vec2) -> bool {
+        self.in_reach(pos, pos, Hero::CONTROL_RANGE)
+    }
+}
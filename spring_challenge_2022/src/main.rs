@@ -1,116 +1,59 @@
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
-use std::fmt;
-use std::io;
-use std::ops::{Add, Mul, Sub};
-use std::time::Instant;
+use std::env;
 
+mod action;
+mod assignment;
+mod attacker;
+mod clock;
+mod enemy;
 #[cfg(test)]
 mod tests;
+mod tracker;
 
-macro_rules! parse_input {
-    ($x:expr, $t:ident) => {
-        $x.trim().parse::<$t>().unwrap()
-    };
-}
+use action::{write_actions, HeroAction, SpellTarget};
+use attacker::Planner;
+use cg_common::spring2022 as rules;
+use cg_common::{Console, ErrorKind, FromLine, ParseError, Vec2f, Words};
+use clock::TurnClock;
+use enemy::EnemyTracker;
+use tracker::MonsterTracker;
+
+/// Position on the map, in map units
+pub type Vec2 = Vec2f;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct Vec2 {
-    x: f32,
-    y: f32,
+/// The bases are in two opposite corners of the map
+pub trait MapCorner {
+    const MAX: Self;
+
+    fn opposite_corner(&self) -> Self;
 }
 
-impl Vec2 {
-    pub const ZERO: Self = Self { x: 0.0, y: 0.0 };
-    pub const MAX: Self = Self {
-        x: 17630.0,
-        y: 9000.0,
-    };
+impl MapCorner for Vec2 {
+    const MAX: Self = rules::MAP;
 
-    pub fn opposite_corner(&self) -> Self {
+    fn opposite_corner(&self) -> Self {
         if *self == Self::ZERO {
             Self::MAX
         } else {
             Self::ZERO
         }
     }
-
-    pub fn distance(&self, other: &Self) -> f32 {
-        let dx = (other.x - self.x) as f32;
-        let dy = (other.y - self.y) as f32;
-        (dx * dx + dy * dy).sqrt()
-    }
-
-    pub fn magnitude(&self) -> f32 {
-        self.distance(&Self::ZERO)
-    }
-
-    pub fn normalize(&self) -> Self {
-        let m = self.magnitude();
-        Self {
-            x: self.x as f32 / m,
-            y: self.y as f32 / m,
-        }
-    }
-
-    pub fn perp(&self) -> Self {
-        Self {
-            x: -self.y,
-            y: self.x,
-        }
-    }
-
-    pub fn in_bounds(&self, bounds: &Vec2) -> bool {
-        self.x >= 0.0 && self.x <= bounds.x && self.y >= 0.0 && self.y <= bounds.y
-    }
-}
-
-impl fmt::Display for Vec2 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {}", self.x as i32, self.y as i32)
-    }
-}
-
-impl Add for Vec2 {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self::Output {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
-    }
-}
-
-impl Sub for Vec2 {
-    type Output = Self;
-
-    fn sub(self, other: Self) -> Self::Output {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
-    }
 }
 
-impl Mul<f32> for Vec2 {
-    type Output = Self;
-
-    fn mul(self, rhs: f32) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
+/// Direction of `v`, a zero vector has none and stays zero
+fn unit(v: Vec2) -> Vec2 {
+    if v == Vec2::ZERO {
+        v
+    } else {
+        v.normalize()
     }
 }
 
-impl Eq for Vec2 {}
-
 #[derive(Debug, Clone)]
 pub struct Patrol {
     center: Vec2,
     start_angle: f32,
-    end_angle: f32,
     radius: f32,
     n_segments: f32,
     step: f32,
@@ -130,7 +73,6 @@ impl Patrol {
         let mut new = Self {
             center,
             start_angle,
-            end_angle,
             radius,
             n_segments: n,
             step,
@@ -147,29 +89,29 @@ impl Patrol {
         for i in 0..(self.n_segments as usize) + 1 {
             let x = self.center.x + self.radius * (self.start_angle + self.step * i as f32).cos();
             let y = self.center.y + self.radius * (self.start_angle + self.step * i as f32).sin();
-            self.points.push(Vec2 { x: x, y: y });
+            self.points.push(Vec2 { x, y });
         }
     }
 
     pub fn get(&self) -> Vec2 {
-        self.points[self.i as usize].clone()
+        self.points[self.i as usize]
     }
 
     pub fn get_next(&mut self) -> Vec2 {
-        let t = self.points[self.i as usize].clone();
+        let t = self.points[self.i as usize];
         let l = (self.points.len() - 1) as i32;
         if self.i == l {
             self.d = -1;
         } else if self.i == 0 {
             self.d = 1;
         }
-        self.i = self.i + self.d;
+        self.i += self.d;
         t
     }
 
     pub fn get_next_offensively(&mut self) -> Vec2 {
         let l = (self.points.len() - 1) as i32;
-        self.i = (self.i) % l;
+        self.i %= l;
         self.get_next()
     }
 }
@@ -184,13 +126,18 @@ struct Hero {
 }
 
 impl Hero {
-    const VIEW_RANGE: f32 = 2200.0;
-    const SPEED: f32 = 800.0;
-    const DMG: i32 = 2;
-    const ATTACK_RANGE: f32 = 800.0;
-    const SHIELD_RANGE: f32 = 2200.0;
-    const WIND_RANGE: f32 = 1280.0;
-    const CONTROL_RANGE: f32 = 2200.0;
+    const VIEW_RANGE: f32 = rules::HERO_VIEW_RANGE;
+    const SPEED: f32 = rules::HERO_SPEED;
+    const DMG: i32 = rules::HERO_DAMAGE;
+    const ATTACK_RANGE: f32 = rules::ATTACK_RANGE;
+    const SHIELD_RANGE: f32 = rules::SHIELD_RANGE;
+    const WIND_RANGE: f32 = rules::WIND_RANGE;
+    const WIND_PUSH: f32 = rules::WIND_PUSH;
+    const SHIELD_DURATION: i32 = rules::SHIELD_DURATION;
+    const CONTROL_RANGE: f32 = rules::CONTROL_RANGE;
+    const SPELL_COST: u32 = rules::SPELL_COST as u32;
+    const PRE_SHIELD_MANA: u32 = 50;
+    const PRE_SHIELD_ETA: i32 = 10;
 
     pub fn new(base: &Vec2, attack: bool) -> Self {
         if attack {
@@ -213,48 +160,46 @@ impl Hero {
         self.charmed = charmed;
     }
 
-    pub fn move_to(&self, pos: &Vec2, yell: Option<String>) {
-        println!(
-            "MOVE {} {}:{}",
-            pos,
-            self.id,
-            yell.unwrap_or("n/a".to_string())
-        );
+    fn message(&self, yell: Option<String>) -> String {
+        format!("{}:{}", self.id, yell.unwrap_or("n/a".to_string()))
     }
 
-    pub fn wait(&self, yell: Option<String>) {
-        println!("WAIT {}:{}", self.id, yell.unwrap_or("n/a".to_string()));
+    pub fn move_to(&self, pos: &Vec2, yell: Option<String>) -> HeroAction {
+        HeroAction::Move {
+            target: *pos,
+            message: self.message(yell),
+        }
     }
 
-    pub fn wind(&self, t: &Vec2, yell: Option<String>) {
-        println!(
-            "SPELL WIND {} {}:{}",
-            t,
-            self.id,
-            yell.unwrap_or("n/a".to_string())
-        );
+    pub fn wait(&self, yell: Option<String>) -> HeroAction {
+        HeroAction::Wait {
+            message: self.message(yell),
+        }
     }
 
-    pub fn shield(&self, tid: i32, yell: Option<String>) {
-        println!(
-            "SPELL SHIELD {} {}:{}",
-            tid,
-            self.id,
-            yell.unwrap_or("n/a".to_string())
-        );
+    pub fn wind(&self, t: &Vec2, yell: Option<String>) -> HeroAction {
+        HeroAction::Wind {
+            target: *t,
+            message: self.message(yell),
+        }
     }
 
-    pub fn control(&self, tid: i32, t: &Vec2, yell: Option<String>) {
-        println!(
-            "SPELL CONTROL {} {} {}:{}",
-            tid,
-            t,
-            self.id,
-            yell.unwrap_or("n/a".to_string())
-        );
+    pub fn shield(&self, tid: i32, yell: Option<String>) -> HeroAction {
+        HeroAction::Shield {
+            id: tid,
+            message: self.message(yell),
+        }
+    }
+
+    pub fn control(&self, tid: i32, t: &Vec2, yell: Option<String>) -> HeroAction {
+        HeroAction::Control {
+            id: tid,
+            target: *t,
+            message: self.message(yell),
+        }
     }
 
-    pub fn patrol(&mut self, monsters_none: &mut Vec<Monster>) {
+    pub fn patrol(&mut self, monsters_none: &mut [Monster]) -> HeroAction {
         // Check if there are neutral monsters
         // if there's is, sort them by distance from the hero
         // iterate through those and if distance from patrolling center
@@ -268,13 +213,12 @@ impl Hero {
             for m in monsters_none.iter() {
                 if self.patrol.center.distance(&m.pos) < 9000.0 {
                     let (t, _) = self.find_intercept(m);
-                    self.move_to(&t, Some(format!("N{}", m.id)));
-                    return;
+                    return self.move_to(&t, Some(format!("N{}", m.id)));
                 }
             }
         }
         let t = self.patrol.get_next();
-        self.move_to(&t, Some(format!("PAT")));
+        self.move_to(&t, Some("PAT".to_string()))
     }
 
     pub fn time_to_kill(&self, monster: &Monster) -> (Vec2, i32) {
@@ -288,72 +232,75 @@ impl Hero {
 
     /// Attempt to find an ideal target for intercepting the monster
     pub fn find_intercept(&self, monster: &Monster) -> (Vec2, i32) {
-        let mut m = monster.clone();
-        let mut i: f32 = 0.0;
-        while self.pos.distance(&m.pos) > Self::SPEED * i + Self::ATTACK_RANGE {
-            i += 1.0;
-            m.simulate_move();
-        }
-        m.simulate_move();
-        (m.pos, i as i32)
+        monster.intercept(&self.pos, Self::ATTACK_RANGE)
     }
 
+    /// Deals with the monster assigned by `Game::defend`, `monsters_me` are
+    /// the candidates for sending away with CONTROL
     pub fn defend(
         &mut self,
-        monsters_me: &mut BinaryHeap<Monster>,
-        monsters_none: &mut Vec<Monster>,
+        target: Option<Monster>,
+        monsters_me: &[Monster],
+        monsters_none: &mut [Monster],
         mana: u32,
-    ) {
-        let m_opt = monsters_me.peek();
-        if m_opt.is_some() {
-            let m = m_opt.unwrap();
-            let mid = m.id;
-            let (t, ttk) = self.time_to_kill(m);
+        enemies: &EnemyTracker,
+    ) -> HeroAction {
+        if let Some(m) = target {
+            // don't get dragged away while the monster is closing in
+            if mana > Self::PRE_SHIELD_MANA
+                && self.shield == 0
+                && m.eta < Self::PRE_SHIELD_ETA
+                && enemies.can_control(&self.pos)
+            {
+                return self.shield(self.id, Some("PS".to_string()));
+            }
+            let (t, ttk) = self.time_to_kill(&m);
             if ttk < m.eta {
                 // I can delay me running for it
                 if ttk < m.eta - 2 {
-                    for m in monsters_me.iter() {
+                    let corner = self.patrol.center.opposite_corner();
+                    for other in monsters_me.iter() {
+                        let mut controlled = *other;
                         if mana > 10
-                            && !m.reaching
-                            && m.shield == 0
-                            && self.pos.distance(&m.pos) < Self::CONTROL_RANGE
+                            && !other.reaching
+                            && controlled.apply_control(&self.pos, &corner)
                         {
-                            self.control(
-                                m.id,
-                                &self.patrol.center.opposite_corner(),
-                                Some("CM".to_string()),
-                            );
-                            let _ = monsters_me.pop();
-                            return;
+                            return self.control(other.id, &corner, Some("CM".to_string()));
                         }
                     }
                 }
-                let _ = monsters_me.pop();
-            } else if mana > 10 && m.shield == 0 && self.pos.distance(&m.pos) < Self::WIND_RANGE {
-                self.wind(
-                    &self.patrol.center.opposite_corner(),
-                    Some(format!("diff {}", m.eta - ttk)),
-                );
-                return;
+            } else if mana > 10 {
+                // push it away if the wind actually reaches it
+                let corner = self.patrol.center.opposite_corner();
+                let mut winded = m;
+                if winded.apply_wind(&self.pos, &corner) {
+                    return self.wind(&corner, Some(format!("eta {}", winded.eta)));
+                }
             }
-            self.move_to(&t, Some(format!("M{}", mid)));
-            return;
+            if enemies.threatens(&m) && self.pos.distance(&m.pos) > Self::ATTACK_RANGE {
+                // wait where a wind towards the base would drop it
+                let base = self.patrol.center;
+                let d = (m.pos.distance(&base) - Monster::BASE_DAMAGE_RANGE)
+                    .clamp(0.0, Self::WIND_PUSH);
+                let spot = m.pos + unit(base - m.pos) * d;
+                return self.move_to(&spot, Some(format!("P{}", m.id)));
+            }
+            return self.move_to(&t, Some(format!("M{}", m.id)));
         }
-        self.patrol(monsters_none);
+        self.patrol(monsters_none)
     }
 
     pub fn attack(
         &mut self,
-        monsters_enemy: &mut Vec<Monster>,
-        monsters_none: &mut Vec<Monster>,
+        monsters_enemy: &mut [Monster],
+        monsters_none: &mut [Monster],
         mana: u32,
-    ) {
+    ) -> HeroAction {
         if mana > 120 {
             // attempt to wind
             for m in monsters_enemy.iter() {
                 if m.shield == 0 && m.pos.distance(&self.pos) < Self::WIND_RANGE {
-                    self.wind(&self.patrol.center, Some("E".to_string()));
-                    return;
+                    return self.wind(&self.patrol.center, Some("E".to_string()));
                 }
             }
             for m in monsters_none.iter() {
@@ -361,23 +308,21 @@ impl Hero {
                     && m.pos.distance(&self.pos) < Self::WIND_RANGE
                     && m.pos.distance(&self.patrol.center) < 7200.0
                 {
-                    self.wind(&self.patrol.center, Some("N".to_string()));
-                    return;
+                    return self.wind(&self.patrol.center, Some("N".to_string()));
                 }
                 if m.shield == 0 && m.pos.distance(&self.pos) < Self::CONTROL_RANGE {
-                    self.control(m.id, &self.patrol.center, Some("N".to_string()));
-                    return;
+                    return self.control(m.id, &self.patrol.center, Some("N".to_string()));
                 }
             }
             // attempt to shield
             for m in monsters_enemy.iter() {
-                if m.shield == 0 && m.eta < 15 && m.pos.distance(&self.pos) < Self::SHIELD_RANGE {
-                    self.shield(m.id, Some("E".to_string()));
-                    return;
+                let mut shielded = *m;
+                if m.eta < 15 && shielded.apply_shield(&self.pos) {
+                    return self.shield(m.id, Some("E".to_string()));
                 }
             }
         }
-        self.patrol(monsters_none);
+        self.patrol(monsters_none)
     }
 }
 
@@ -393,7 +338,7 @@ impl Default for Hero {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 struct Monster {
     id: i32,
     pos: Vec2,
@@ -404,12 +349,17 @@ struct Monster {
     target: Option<Vec2>,
     reaching: bool,
     eta: i32,
+    control: Option<Vec2>,
+    pushed: bool,
 }
 
 impl Monster {
-    const SPEED: f32 = 400.0;
-    const AGRRO_RANGE: f32 = 5000.0;
+    const SPEED: f32 = rules::MONSTER_SPEED;
+    const AGRRO_RANGE: f32 = rules::AGGRO_RANGE;
+    const BASE_DAMAGE_RANGE: f32 = rules::BASE_DAMAGE_RANGE;
+    const ETA_HORIZON: i32 = 100;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: i32,
         pos: Vec2,
@@ -430,47 +380,116 @@ impl Monster {
             target,
             reaching,
             eta: i32::MAX,
+            control: None,
+            pushed: false,
         };
         new.eta();
         new
     }
 
-    // pub fn simulate()
-
+    /// Turns until the monster damages its target, `i32::MAX` when it
+    /// has no target or leaves the map first
     fn eta(&mut self) {
-        self.eta = if self.target.is_some() {
-            let t = self.target.unwrap();
-            let mut approach: f32 = 0.0;
-            let mut p: Vec2 = self.pos.clone();
-            let mut v: Vec2 = self.velocity.normalize() * Self::SPEED;
-            // find round at which the monster is < 5000
-            while (p + (v * approach)).distance(&t) > Self::AGRRO_RANGE {
-                approach += 1.0;
+        self.eta = i32::MAX;
+        let Some(t) = self.target else {
+            return;
+        };
+        let mut m = *self;
+        for i in 0..Self::ETA_HORIZON {
+            if m.pos.distance(&t) <= Self::BASE_DAMAGE_RANGE {
+                self.eta = i;
+                return;
             }
-            p = p + (v * approach);
-            // get new vector towards target
-            v = t - p;
-            // get eta to target == reach
-            let reach = (v.magnitude() - 300.0).max(0.0) / Self::SPEED;
-            // return approach + reach
-            if reach == 0.0 {
-                0
-            } else {
-                (reach + approach).trunc() as i32 + 1
+            if m.despawned() {
+                return;
             }
-        } else {
-            i32::MAX
-        };
+            m.simulate_move();
+        }
+    }
+
+    /// Base the monster ends up at if it keeps its current trajectory
+    fn threat(&self) -> Option<Vec2> {
+        let mut p = self.pos;
+        while p.in_bounds(&Vec2::MAX) {
+            for base in [Vec2::ZERO, Vec2::MAX] {
+                if p.distance(&base) <= Self::AGRRO_RANGE {
+                    return Some(base);
+                }
+            }
+            if self.velocity == Vec2::ZERO {
+                break;
+            }
+            p += self.velocity;
+        }
+        None
+    }
+
+    /// Turns a hero standing at `pos` needs to get within `range` of the
+    /// monster, and where the monster is the turn after
+    pub fn intercept(&self, pos: &Vec2, range: f32) -> (Vec2, i32) {
+        let mut m = *self;
+        let mut i: f32 = 0.0;
+        while pos.distance(&m.pos) > Hero::SPEED * i + range {
+            i += 1.0;
+            m.simulate_move();
+        }
+        m.simulate_move();
+        (m.pos, i as i32)
+    }
+
+    pub fn despawned(&self) -> bool {
+        !self.pos.in_bounds(&Vec2::MAX)
+    }
+
+    /// Pushes the monster if it is in range of a wind cast from `caster`
+    /// towards `t`, pushed monsters don't move on their own that turn
+    pub fn apply_wind(&mut self, caster: &Vec2, t: &Vec2) -> bool {
+        if self.shield > 0 || *t == *caster || self.pos.distance(caster) > Hero::WIND_RANGE {
+            return false;
+        }
+        self.pos += (*t - *caster).normalize() * Hero::WIND_PUSH;
+        self.pushed = true;
+        self.target = self.threat();
+        self.eta();
+        true
+    }
+
+    /// Redirects the monster towards `t` on its next move
+    pub fn apply_control(&mut self, caster: &Vec2, t: &Vec2) -> bool {
+        if self.shield > 0 || self.pos.distance(caster) > Hero::CONTROL_RANGE {
+            return false;
+        }
+        self.velocity = unit(*t - self.pos) * Self::SPEED;
+        self.control = Some(*t);
+        self.charmed = true;
+        self.target = self.threat();
+        self.eta();
+        true
+    }
+
+    pub fn apply_shield(&mut self, caster: &Vec2) -> bool {
+        if self.shield > 0 || self.pos.distance(caster) > Hero::SHIELD_RANGE {
+            return false;
+        }
+        self.shield = Hero::SHIELD_DURATION;
+        true
     }
 
     fn simulate_move(&mut self) {
-        if self.target.is_some() {
-            let t = self.target.unwrap();
+        if let Some(t) = self.control.take() {
+            self.velocity = unit(t - self.pos) * Self::SPEED;
+        } else if let Some(t) = self.target {
             if self.pos.distance(&t) <= Self::AGRRO_RANGE {
-                self.velocity = (t - self.pos).normalize() * Self::SPEED;
+                self.velocity = unit(t - self.pos) * Self::SPEED;
             }
         }
-        self.pos = self.pos + self.velocity;
+        if self.pushed {
+            self.pushed = false;
+        } else {
+            self.pos += self.velocity;
+        }
+        self.charmed = false;
+        self.shield = (self.shield - 1).max(0);
     }
 }
 
@@ -489,6 +508,9 @@ impl Ord for Monster {
     }
 }
 
+// Positions are never NaN
+impl Eq for Monster {}
+
 // `PartialOrd` needs to be implemented as well.
 impl PartialOrd for Monster {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -500,15 +522,21 @@ struct Player {
     hp: u32,
     mana: u32,
     base: Vec2,
+    spent: u32,
+    income: f32,
 }
 
 impl Player {
-    pub fn update(&mut self) {
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        self.hp = parse_input!(inputs[0], u32); // Your base health
-        self.mana = parse_input!(inputs[1], u32); // Ignore in the first league; Spend ten mana to cast a spell
+    pub fn update(&mut self, mut inputs: Words) -> Result<(), ParseError> {
+        let previous = self.mana;
+        self.hp = inputs.field("health")?; // Your base health
+        self.mana = inputs.field("mana")?; // Spend ten mana to cast a spell
+
+        // mana gained by hitting monsters, averaged over the recent turns
+        let gained = (self.mana + self.spent).saturating_sub(previous);
+        self.income = self.income * 0.9 + gained as f32 * 0.1;
+        self.spent = 0;
+        Ok(())
     }
 }
 
@@ -518,6 +546,8 @@ impl Default for Player {
             hp: 3,
             mana: 0,
             base: Vec2::ZERO,
+            spent: 0,
+            income: 0.0,
         }
     }
 }
@@ -529,22 +559,24 @@ struct Game {
     monsters_me: BinaryHeap<Monster>,
     monsters_enemy: Vec<Monster>,
     monsters_none: Vec<Monster>,
+    enemies: EnemyTracker,
+    tracker: MonsterTracker,
 }
 
 impl Game {
-    pub fn new() -> Self {
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let inputs = input_line.split(" ").collect::<Vec<_>>();
-        let base_x = parse_input!(inputs[0], f32); // The corner of the map representing your base
-        let base_y = parse_input!(inputs[1], f32);
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let _heroes_per_player = parse_input!(input_line, i32); // Always 3
-        let base = Vec2 {
-            x: base_x,
-            y: base_y,
-        };
+    /// Mana the attacker leaves to the defenders
+    const DEFENCE_MANA: u32 = 30;
+    const MIN_DAMAGE_PER_MANA: f32 = 0.02;
+
+    pub fn new(console: &mut Console) -> Result<Self, ParseError> {
+        let mut inputs = console.words("base")?;
+        let base_x = inputs.field("base_x")?; // The corner of the map representing your base
+        let base_y = inputs.field("base_y")?;
+        let _heroes_per_player: i32 = console.parse("heroes_per_player")?; // Always 3
+        Ok(Self::from_base(Vec2::new(base_x, base_y)))
+    }
+
+    pub fn from_base(base: Vec2) -> Self {
         let enemy_base = base.opposite_corner();
         Self {
             me: Player {
@@ -563,9 +595,139 @@ impl Game {
             monsters_me: BinaryHeap::new(),
             monsters_enemy: Vec::new(),
             monsters_none: Vec::new(),
+            enemies: EnemyTracker::default(),
+            tracker: MonsterTracker::default(),
         }
     }
 
+    /// Both defenders act on one shared assignment, so they never chase the
+    /// same monster unless it takes two of them to stop it
+    fn defend(&mut self) -> [HeroAction; 2] {
+        let monsters = self.monsters_me.iter().copied().collect::<Vec<_>>();
+        let targets = assignment::assign(&self.my_heroes[..2], &monsters);
+        let mut candidates = monsters.clone();
+        let mut actions = Vec::with_capacity(2);
+        for (hero, target) in self.my_heroes[..2].iter_mut().zip(targets) {
+            let action = hero.defend(
+                target.map(|j| monsters[j]),
+                &candidates,
+                &mut self.monsters_none,
+                self.me.mana,
+                &self.enemies,
+            );
+            // don't control the same monster twice
+            if let Some(id) = action.target_id() {
+                candidates.retain(|m| m.id != id);
+            }
+            actions.push(action);
+        }
+        actions.try_into().unwrap()
+    }
+
+    /// Attacker follows the best spell plan when it beats the greedy attack
+    /// on expected damage per mana, planning further ahead while the clock
+    /// allows
+    fn attack(&mut self, clock: &TurnClock) -> HeroAction {
+        let greedy = self.my_heroes[2].attack(
+            &mut self.monsters_enemy,
+            &mut self.monsters_none,
+            self.me.mana,
+        );
+        let turn = self.tracker.turn();
+        let visible = self
+            .tracker
+            .iter()
+            .filter(|t| t.is_visible(turn))
+            .map(|t| t.monster)
+            .collect::<Vec<_>>();
+        let enemy_heroes = self.enemies.recent().map(|h| h.pos).collect::<Vec<_>>();
+        let planner = Planner {
+            hero: &self.my_heroes[2],
+            enemy_base: self.enemy.base,
+            enemy_heroes: &enemy_heroes,
+            mana: self.me.mana.saturating_sub(Self::DEFENCE_MANA),
+            income: self.me.income,
+        };
+        let greedy_score = planner.score(&greedy, &visible);
+        let deepest = clock.deepen(Planner::MAX_TURNS as usize, |horizon| {
+            planner.best_plan(&visible, horizon as i32)
+        });
+        match deepest {
+            Some((Some(plan), horizon)) => {
+                eprintln!(
+                    "plan {:?} on {} to {} in {}/{} turns: {:.3} dmg/mana, greedy {:.3}",
+                    plan.spells,
+                    plan.monster,
+                    plan.end,
+                    plan.turns,
+                    horizon,
+                    plan.damage_per_mana(),
+                    greedy_score
+                );
+                if plan.damage_per_mana() >= Self::MIN_DAMAGE_PER_MANA
+                    && plan.damage_per_mana() > greedy_score
+                {
+                    plan.actions[0].clone()
+                } else {
+                    greedy
+                }
+            }
+            _ => greedy,
+        }
+    }
+
+    /// Visible entity a spell can be cast on
+    fn spell_target(&self, id: i32) -> Option<SpellTarget> {
+        let turn = self.tracker.turn();
+        let target = |pos, is_mine| SpellTarget { pos, is_mine };
+        self.my_heroes
+            .iter()
+            .find(|h| h.id == id)
+            .map(|h| target(h.pos, true))
+            .or_else(|| {
+                self.tracker
+                    .iter()
+                    .find(|t| t.monster.id == id && t.is_visible(turn))
+                    .map(|t| target(t.monster.pos, false))
+            })
+    }
+
+    /// Decides the whole turn, all three heroes
+    fn play(&mut self, clock: &TurnClock) -> [HeroAction; 3] {
+        eprintln!(
+            "me {}, enemy {}, none {}",
+            self.monsters_me.len(),
+            self.monsters_enemy.len(),
+            self.monsters_none.len()
+        );
+        // defenders
+        let [first, second] = self.defend();
+        let mut actions = [
+            first,
+            second,
+            // attacker
+            self.attack(clock),
+        ];
+        self.validate_actions(&mut actions);
+        actions
+    }
+
+    /// Replaces actions we can't afford or reach with WAIT
+    fn validate_actions(&mut self, actions: &mut [HeroAction; 3]) {
+        let mut mana = self.me.mana;
+        for (hero, action) in self.my_heroes.iter().zip(actions.iter_mut()) {
+            let target = action.target_id().and_then(|id| self.spell_target(id));
+            if action.is_valid(hero, mana, target.as_ref()) {
+                mana -= action.cost();
+            } else {
+                eprintln!("invalid {}", action);
+                *action = hero.wait(Some("invalid".to_string()));
+            }
+        }
+        self.me.spent = self.me.mana - mana;
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn update_monster(
         &mut self,
         id: i32,
@@ -577,72 +739,69 @@ impl Game {
         threat_for: i32,
         reaching: bool,
     ) {
-        match threat_for {
-            1 => {
-                let new = Monster::new(
-                    id,
-                    pos,
-                    shield,
-                    charmed,
-                    hp,
-                    velocity,
-                    Some(self.me.base),
-                    reaching,
-                );
-                if new.pos.distance(&self.me.base) > 9000.0 {
-                    self.monsters_none.push(new)
-                } else {
-                    self.monsters_me.push(new)
+        let target = match threat_for {
+            1 => Some(self.me.base),
+            2 => Some(self.enemy.base),
+            _ => None,
+        };
+        let reaching = reaching && target.is_some();
+        self.tracker.observe(
+            Monster::new(id, pos, shield, charmed, hp, velocity, target, reaching),
+            threat_for,
+        );
+    }
+
+    /// Sorts all tracked monsters, including the ones out of sight
+    fn sort_monsters(&mut self) {
+        for t in self.tracker.iter() {
+            match t.threat_for {
+                1 if t.monster.pos.distance(&self.me.base) <= 9000.0 => {
+                    self.monsters_me.push(t.monster)
                 }
+                2 => self.monsters_enemy.push(t.monster),
+                _ => self.monsters_none.push(t.monster),
             }
-            2 => self.monsters_enemy.push(Monster::new(
-                id,
-                pos,
-                shield,
-                charmed,
-                hp,
-                velocity,
-                Some(self.enemy.base),
-                reaching,
-            )),
-            _ => self.monsters_none.push(Monster::new(
-                id, pos, shield, charmed, hp, velocity, None, false,
-            )),
         }
     }
 
-    pub fn update(&mut self, buf: &mut String) {
-        // We should somehow track monsters that we've seen, but are not visible now
-        // for now lets clear the monsters before each update
+    /// Reads the turn, `false` once the input has ended
+    pub fn update(
+        &mut self,
+        console: &mut Console,
+        clock: &mut TurnClock,
+    ) -> Result<bool, ParseError> {
+        let Some(me) = console.next_words()? else {
+            return Ok(false);
+        };
+        clock.start();
+
+        // Monsters out of sight are kept in the tracker, the lists are rebuilt from it
+        self.tracker.next_turn();
+        self.enemies.next_turn();
         self.monsters_me.clear();
         self.monsters_enemy.clear();
         self.monsters_none.clear();
 
         // Players hp and mana
-        self.me.update();
-        self.enemy.update();
+        self.me.update(me)?;
+        self.enemy.update(console.words("enemy")?)?;
 
-        buf.clear();
-        io::stdin().read_line(buf).unwrap();
-        let entity_count = parse_input!(buf, usize); // Amount of heros and monsters you can see
+        let entity_count: usize = console.parse("entity_count")?; // Amount of heros and monsters you can see
 
         for _ in 0..entity_count {
-            buf.clear();
-            io::stdin().read_line(buf).unwrap();
-            let inputs = buf.split(" ").collect::<Vec<_>>();
-            let id = parse_input!(inputs[0], i32); // Unique identifier
-            let tp = parse_input!(inputs[1], i32); // 0=monster, 1=your hero, 2=opponent hero
-            let x = parse_input!(inputs[2], f32); // Position of this entity
-            let y = parse_input!(inputs[3], f32);
-            let shield_life = parse_input!(inputs[4], i32); // Ignore for this league; Count down until shield spell fades
-            let is_controlled = parse_input!(inputs[5], i32); // Ignore for this league; Equals 1 when this entity is under a control spell
-            let health = parse_input!(inputs[6], i32); // Remaining health of this monster
-            let vx = parse_input!(inputs[7], f32); // Trajectory of this monster
-            let vy = parse_input!(inputs[8], f32);
-            let near_base = parse_input!(inputs[9], i32); // 0=monster with no target yet, 1=monster targeting a base
-            let threat_for = parse_input!(inputs[10], i32); // Given this monster's trajectory, is it a threat to 1=your base, 2=your opponent's base, 0=neither
-
-            if tp == 1 {
+            let Entity {
+                id,
+                kind,
+                pos,
+                shield_life,
+                is_controlled,
+                health,
+                velocity,
+                near_base,
+                threat_for,
+            } = console.read()?;
+
+            if kind == 1 {
                 let hid = match id {
                     0 | 3 => 0,
                     1 | 4 => 1,
@@ -651,27 +810,71 @@ impl Game {
                         panic!("hero id {}", id)
                     }
                 };
-                self.my_heroes[hid].update(
-                    id,
-                    Vec2 { x: x, y: y },
-                    shield_life,
-                    is_controlled == 1,
-                );
+                self.my_heroes[hid].update(id, pos, shield_life, is_controlled);
             }
 
-            if tp == 0 {
+            if kind == 2 {
+                self.enemies.observe(id, pos, &self.enemy.base);
+            }
+
+            if kind == 0 {
                 self.update_monster(
                     id,
-                    Vec2 { x: x, y: y },
+                    pos,
                     shield_life,
-                    is_controlled == 1,
+                    is_controlled,
                     health,
-                    Vec2 { x: vx, y: vy },
+                    velocity,
                     threat_for,
-                    near_base == 1,
+                    near_base,
                 );
             }
         }
+        self.tracker.update(&self.my_heroes, &self.me.base);
+        self.sort_monsters();
+        Ok(true)
+    }
+}
+
+/// Line of the entity list
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Entity {
+    id: i32,
+    /// 0=monster, 1=your hero, 2=opponent hero
+    kind: i32,
+    pos: Vec2,
+    /// Count down until the shield spell fades
+    shield_life: i32,
+    is_controlled: bool,
+    /// Remaining health of a monster
+    health: i32,
+    /// Trajectory of a monster
+    velocity: Vec2,
+    /// The monster is targeting a base, -1 for heroes
+    near_base: bool,
+    /// Base the monster is a threat to, 1=your base, 2=your opponent's base, 0=neither
+    threat_for: i32,
+}
+
+impl FromLine for Entity {
+    fn from_line(words: &mut Words) -> Result<Self, ParseError> {
+        let id = words.field("id")?;
+        let kind = words.field("type")?;
+        if !(0..=2).contains(&kind) {
+            let reason = format!("unknown entity type {}", kind);
+            return Err(words.error("type", ErrorKind::Invalid(reason)));
+        }
+        Ok(Self {
+            id,
+            kind,
+            pos: Vec2::new(words.field("x")?, words.field("y")?),
+            shield_life: words.field("shield_life")?,
+            is_controlled: words.flag("is_controlled")?,
+            health: words.field("health")?,
+            velocity: Vec2::new(words.field("vx")?, words.field("vy")?),
+            near_base: words.field::<i32>("near_base")? == 1,
+            threat_for: words.field("threat_for")?,
+        })
     }
 }
 
@@ -680,35 +883,38 @@ impl Game {
  * the standard input according to the problem statement.
  **/
 fn main() {
-    let mut game = Game::new();
-    let mut buffer = String::with_capacity(128);
+    let args = env::args().collect::<Vec<_>>();
+    // `replay <file> [turn]` stops after the given turn
+    let mut console = Console::from_args(&args[1..]).unwrap();
+    let last_turn = match args.get(1).map(String::as_str) {
+        Some("replay") => args.get(3).and_then(|t| t.parse::<u32>().ok()),
+        _ => None,
+    };
+    let mut game = Game::new(&mut console).unwrap_or_else(|e| e.exit());
+    let mut clock = TurnClock::default();
 
     // game loop
-    loop {
-        let now = Instant::now();
-        game.update(&mut buffer);
-        eprintln!("{:.3}ms", now.elapsed().as_millis());
-        eprintln!(
-            "me {}, enemy {}, none {}",
-            game.monsters_me.len(),
-            game.monsters_enemy.len(),
-            game.monsters_none.len()
-        );
-        // defender
-        let now = Instant::now();
-        game.my_heroes[0].defend(&mut game.monsters_me, &mut game.monsters_none, game.me.mana);
-        // defender
-        game.my_heroes[1].defend(&mut game.monsters_me, &mut game.monsters_none, game.me.mana);
-        // attacker
-        game.my_heroes[2].attack(
-            &mut game.monsters_enemy,
-            &mut game.monsters_none,
-            game.me.mana,
-        );
-        let another = now.elapsed().as_millis();
-        eprintln!("{:.3}ms", another);
+    while game
+        .update(&mut console, &mut clock)
+        .unwrap_or_else(|e| e.exit())
+    {
+        eprintln!("{:?}", clock.elapsed());
+        let actions = game.play(&clock);
+        let mut commands = Vec::new();
+        write_actions(&mut commands, &actions).unwrap();
+        console.write_turn(&String::from_utf8(commands).unwrap());
+        eprintln!("{:?}", clock.finish());
+        if clock.is_last_turn() {
+            eprintln!("{}", clock);
+        }
+        if last_turn == Some(clock.turn()) {
+            break;
+        }
         // check for critical targets - the ones heading to base
         // check for nearby targets
         // go patrol
     }
+    if !clock.is_last_turn() {
+        eprintln!("{}", clock);
+    }
 }
@@ -14,6 +14,7 @@ fn monster_eta() {
             y: -100.0,
         },
         None,
+        false,
     );
     dbg!(monster.eta);
     assert!(monster.eta == i32::MAX);
@@ -30,6 +31,7 @@ fn monster_eta() {
             y: -100.0,
         },
         Some(Vec2 { x: 0.0, y: 0.0 }),
+        false,
     );
     dbg!(monster.eta);
     assert!(monster.eta == 0);
@@ -46,6 +48,7 @@ fn monster_eta() {
             y: -100.0,
         },
         Some(Vec2 { x: 0.0, y: 0.0 }),
+        false,
     );
     dbg!(monster.eta);
     assert!(monster.eta == 1);
@@ -65,6 +68,7 @@ fn monster_eta() {
             y: -352.0,
         },
         Some(Vec2 { x: 0.0, y: 0.0 }),
+        false,
     );
     dbg!(monster.eta);
     assert!(monster.eta == 14);
@@ -79,3 +83,813 @@ fn patrol_nodes() {
         dbg!((p, patrol.i, patrol.d));
     }
 }
+
+/// The local referee speaks the protocol `Game` reads
+#[test]
+fn game_reads_referee_input() {
+    use referees::Referee;
+    let referee = referees::SpringChallenge2022::new(0);
+    let input = referee.initial_input(1) + &referee.turn_input(1);
+    let recording = input
+        .lines()
+        .map(|l| format!("< {}\n", l))
+        .collect::<String>();
+    let mut console = Console::replay(&recording);
+    let mut game = Game::new(&mut console).unwrap();
+    assert_eq!(game.me.base, Vec2::MAX);
+    let mut clock = clock::TurnClock::default();
+    assert!(game.update(&mut console, &mut clock).unwrap());
+    assert_eq!(game.my_heroes[0].pos, Vec2::new(16216.0, 8151.0));
+    assert!(!game.update(&mut console, &mut clock).unwrap());
+}
+
+fn wild_monster(id: i32, pos: Vec2, velocity: Vec2) -> Monster {
+    Monster::new(id, pos, 0, false, 10, velocity, None, false)
+}
+
+#[test]
+fn tracker_extrapolates_unseen_monsters() {
+    let heroes = [Hero::default()];
+    let mut tracker = tracker::MonsterTracker::default();
+    tracker.next_turn();
+    tracker.observe(
+        wild_monster(
+            7,
+            Vec2 {
+                x: 9000.0,
+                y: 4000.0,
+            },
+            Vec2 { x: 400.0, y: 0.0 },
+        ),
+        0,
+    );
+    tracker.update(&heroes, &Vec2::ZERO);
+    tracker.next_turn();
+    tracker.update(&heroes, &Vec2::ZERO);
+    let t = tracker.iter().find(|t| t.monster.id == 7).unwrap();
+    assert!(!t.is_visible(2));
+    assert_eq!(
+        t.monster.pos,
+        Vec2 {
+            x: 9400.0,
+            y: 4000.0
+        }
+    );
+}
+
+#[test]
+fn tracker_expires_monsters() {
+    let heroes = [Hero::default()];
+    let mut tracker = tracker::MonsterTracker::default();
+    tracker.next_turn();
+    // leaves the map
+    tracker.observe(
+        wild_monster(
+            7,
+            Vec2 {
+                x: 17500.0,
+                y: 4000.0,
+            },
+            Vec2 { x: 400.0, y: 0.0 },
+        ),
+        0,
+    );
+    // should be seen by the base but is not, so it was killed
+    tracker.observe(
+        wild_monster(
+            8,
+            Vec2 {
+                x: 3000.0,
+                y: 3000.0,
+            },
+            Vec2 { x: 0.0, y: 400.0 },
+        ),
+        0,
+    );
+    tracker.observe(
+        wild_monster(
+            9,
+            Vec2 {
+                x: 9000.0,
+                y: 4000.0,
+            },
+            Vec2 { x: 0.0, y: 400.0 },
+        ),
+        0,
+    );
+    tracker.update(&heroes, &Vec2::ZERO);
+    tracker.next_turn();
+    tracker.update(&heroes, &Vec2::ZERO);
+    let ids = tracker.iter().map(|t| t.monster.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![9]);
+}
+
+#[test]
+fn monster_wind() {
+    let base = Vec2::ZERO;
+    let mut monster = Monster::new(
+        0,
+        Vec2 {
+            x: 1000.0,
+            y: 1000.0,
+        },
+        0,
+        false,
+        10,
+        Vec2 {
+            x: -283.0,
+            y: -283.0,
+        },
+        Some(base),
+        true,
+    );
+    assert_eq!(monster.eta, 3);
+    let caster = Vec2 { x: 500.0, y: 500.0 };
+    assert!(monster.apply_wind(&caster, &Vec2::MAX));
+    // pushed away and doesn't move on the turn of the push
+    let pushed = monster.pos;
+    assert!(pushed.distance(&base) > 3000.0);
+    assert!(monster.eta > 3);
+    monster.simulate_move();
+    assert_eq!(monster.pos, pushed);
+
+    // out of range
+    let mut monster = Monster::new(
+        0,
+        Vec2 {
+            x: 3000.0,
+            y: 3000.0,
+        },
+        0,
+        false,
+        10,
+        Vec2::ZERO,
+        None,
+        false,
+    );
+    assert!(!monster.apply_wind(&caster, &Vec2::MAX));
+}
+
+#[test]
+fn monster_control_onto_itself() {
+    let caster = Vec2 { x: 500.0, y: 500.0 };
+    let pos = Vec2 {
+        x: 1000.0,
+        y: 1000.0,
+    };
+    let mut monster = wild_monster(0, pos, Vec2 { x: 0.0, y: 400.0 });
+    assert!(monster.apply_control(&caster, &pos));
+    // nowhere to go, rather than NaN
+    assert_eq!(monster.velocity, Vec2::ZERO);
+    monster.simulate_move();
+    assert_eq!(monster.pos, pos);
+}
+
+#[test]
+fn monster_shield_blocks_spells() {
+    let caster = Vec2 { x: 500.0, y: 500.0 };
+    let mut monster = Monster::new(
+        0,
+        Vec2 {
+            x: 1000.0,
+            y: 1000.0,
+        },
+        0,
+        false,
+        10,
+        Vec2 {
+            x: -283.0,
+            y: -283.0,
+        },
+        Some(Vec2::ZERO),
+        true,
+    );
+    assert!(monster.apply_shield(&caster));
+    assert!(!monster.apply_wind(&caster, &Vec2::MAX));
+    assert!(!monster.apply_control(&caster, &Vec2::MAX));
+    for _ in 0..Hero::SHIELD_DURATION {
+        monster.simulate_move();
+    }
+    assert_eq!(monster.shield, 0);
+}
+
+#[test]
+fn monster_control_and_despawn() {
+    let caster = Vec2 {
+        x: 9000.0,
+        y: 1000.0,
+    };
+    let mut monster = Monster::new(
+        0,
+        Vec2 {
+            x: 9000.0,
+            y: 1000.0,
+        },
+        0,
+        false,
+        10,
+        Vec2 { x: 0.0, y: 400.0 },
+        None,
+        false,
+    );
+    // sent out of the map through the top edge
+    assert!(monster.apply_control(&caster, &Vec2 { x: 9000.0, y: 0.0 }));
+    assert_eq!(monster.target, None);
+    monster.simulate_move();
+    assert_eq!(
+        monster.pos,
+        Vec2 {
+            x: 9000.0,
+            y: 600.0
+        }
+    );
+    monster.simulate_move();
+    monster.simulate_move();
+    assert!(monster.despawned());
+
+    // redirected towards the enemy base now threatens it
+    let mut monster = Monster::new(
+        0,
+        Vec2 {
+            x: 13000.0,
+            y: 6000.0,
+        },
+        0,
+        false,
+        10,
+        Vec2 { x: -400.0, y: 0.0 },
+        None,
+        false,
+    );
+    let caster = monster.pos;
+    assert!(monster.apply_control(&caster, &Vec2::MAX));
+    assert_eq!(monster.target, Some(Vec2::MAX));
+    assert!(monster.eta < Monster::ETA_HORIZON);
+}
+
+#[test]
+fn hero_action_validation() {
+    let mut game = Game::from_base(Vec2::ZERO);
+    game.me.mana = 15;
+    for (i, hero) in game.my_heroes.iter_mut().enumerate() {
+        hero.update(
+            i as i32,
+            Vec2 {
+                x: 1000.0,
+                y: 1000.0,
+            },
+            0,
+            false,
+        );
+    }
+    game.tracker.next_turn();
+    game.tracker.observe(
+        wild_monster(
+            7,
+            Vec2 {
+                x: 2000.0,
+                y: 2000.0,
+            },
+            Vec2::ZERO,
+        ),
+        0,
+    );
+    game.tracker.observe(
+        wild_monster(
+            8,
+            Vec2 {
+                x: 8000.0,
+                y: 2000.0,
+            },
+            Vec2::ZERO,
+        ),
+        0,
+    );
+    let far = Vec2 {
+        x: 5000.0,
+        y: 5000.0,
+    };
+    // out of range
+    let mut actions = [
+        game.my_heroes[0].control(8, &far, None),
+        game.my_heroes[1].shield(7, None),
+        game.my_heroes[2].wind(&far, None),
+    ];
+    game.validate_actions(&mut actions);
+    assert_eq!(
+        actions[0],
+        game.my_heroes[0].wait(Some("invalid".to_string()))
+    );
+    assert_eq!(actions[1], game.my_heroes[1].shield(7, None));
+    // only enough mana for one spell
+    assert_eq!(
+        actions[2],
+        game.my_heroes[2].wait(Some("invalid".to_string()))
+    );
+    // our own heroes can be shielded but not controlled
+    let mut actions = [
+        game.my_heroes[0].control(1, &far, None),
+        game.my_heroes[1].shield(1, None),
+        game.my_heroes[2].wait(None),
+    ];
+    game.validate_actions(&mut actions);
+    assert_eq!(
+        actions[0],
+        game.my_heroes[0].wait(Some("invalid".to_string()))
+    );
+    assert_eq!(actions[1], game.my_heroes[1].shield(1, None));
+}
+
+#[test]
+fn hero_action_output() {
+    let mut hero = Hero::default();
+    hero.update(1, Vec2::ZERO, 0, false);
+    let actions = [
+        hero.move_to(&Vec2 { x: 10.4, y: 20.0 }, Some("PAT".to_string())),
+        hero.control(7, &Vec2::MAX, None),
+        hero.wait(None),
+    ];
+    let mut out = Vec::new();
+    write_actions(&mut out, &actions).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "MOVE 10 20 1:PAT\nSPELL CONTROL 7 17630 9000 1:n/a\nWAIT 1:n/a\n"
+    );
+}
+
+fn hero_at(id: i32, pos: Vec2) -> Hero {
+    let mut hero = Hero::default();
+    hero.update(id, pos, 0, false);
+    hero
+}
+
+fn base_monster(id: i32, pos: Vec2, hp: i32) -> Monster {
+    let velocity = (Vec2::ZERO - pos).normalize() * Monster::SPEED;
+    Monster::new(id, pos, 0, false, hp, velocity, Some(Vec2::ZERO), true)
+}
+
+#[test]
+fn assignment_kill_time() {
+    assert_eq!(assignment::kill_time(&[2], 10), 7);
+    assert_eq!(assignment::kill_time(&[2, 2], 10), 5);
+    assert_eq!(assignment::kill_time(&[0, 4], 10), 5);
+    assert_eq!(assignment::kill_time(&[], 10), i32::MAX);
+}
+
+#[test]
+fn assignment_splits_defenders() {
+    let heroes = [
+        hero_at(
+            0,
+            Vec2 {
+                x: 4000.0,
+                y: 500.0,
+            },
+        ),
+        hero_at(
+            1,
+            Vec2 {
+                x: 500.0,
+                y: 4000.0,
+            },
+        ),
+    ];
+    let monsters = [
+        base_monster(
+            10,
+            Vec2 {
+                x: 600.0,
+                y: 4400.0,
+            },
+            10,
+        ),
+        base_monster(
+            11,
+            Vec2 {
+                x: 4400.0,
+                y: 600.0,
+            },
+            10,
+        ),
+    ];
+    let targets = assignment::assign(&heroes, &monsters);
+    assert_eq!(targets, vec![Some(1), Some(0)]);
+}
+
+#[test]
+fn assignment_doubles_up_on_danger() {
+    let heroes = [
+        hero_at(
+            0,
+            Vec2 {
+                x: 3000.0,
+                y: 3000.0,
+            },
+        ),
+        hero_at(
+            1,
+            Vec2 {
+                x: 3100.0,
+                y: 2900.0,
+            },
+        ),
+    ];
+    // too tough for a single hero to kill before it reaches the base
+    let monsters = [
+        base_monster(
+            10,
+            Vec2 {
+                x: 2800.0,
+                y: 2800.0,
+            },
+            24,
+        ),
+        base_monster(
+            11,
+            Vec2 {
+                x: 9000.0,
+                y: 7000.0,
+            },
+            10,
+        ),
+    ];
+    let targets = assignment::assign(&heroes, &monsters);
+    assert_eq!(targets, vec![Some(0), Some(0)]);
+}
+
+/// A monster no hero can kill before it hits the base still gets a
+/// defender, who blows it away
+#[test]
+fn assignment_winds_a_leaking_monster() {
+    let mut game = Game::from_base(Vec2::ZERO);
+    game.me.mana = 20;
+    let spots = [(1000.0, 1000.0), (6000.0, 1000.0), (9000.0, 5000.0)];
+    for (i, (hero, (x, y))) in game.my_heroes.iter_mut().zip(spots).enumerate() {
+        hero.update(i as i32, Vec2 { x, y }, 0, false);
+    }
+    let monster = base_monster(10, Vec2 { x: 450.0, y: 450.0 }, 20);
+    assert_eq!(monster.eta, 1);
+    game.monsters_me.push(monster);
+    let targets = assignment::assign(&game.my_heroes[..2], &[monster]);
+    assert_eq!(targets[0], Some(0));
+    let [first, _] = game.defend();
+    assert!(matches!(first, HeroAction::Wind { .. }), "{}", first);
+}
+
+#[test]
+fn attacker_expected_damage() {
+    let hero = hero_at(
+        2,
+        Vec2 {
+            x: 10000.0,
+            y: 5000.0,
+        },
+    );
+    let enemy_heroes = [Vec2 {
+        x: 15000.0,
+        y: 8000.0,
+    }];
+    let planner = attacker::Planner {
+        hero: &hero,
+        enemy_base: Vec2::MAX,
+        enemy_heroes: &enemy_heroes,
+        mana: 100,
+        income: 0.0,
+    };
+    let pos = Vec2 {
+        x: 15500.0,
+        y: 7800.0,
+    };
+    let velocity = (Vec2::MAX - pos).normalize() * Monster::SPEED;
+    let mut monster = Monster::new(9, pos, 0, false, 40, velocity, Some(Vec2::MAX), true);
+    // too tough to kill, but can be pushed away
+    assert_eq!(planner.expected_damage(&monster), 0.25);
+    monster.shield = 12;
+    assert_eq!(planner.expected_damage(&monster), 1.0);
+    // weak enough to be killed
+    monster.hp = 2;
+    assert_eq!(planner.expected_damage(&monster), 0.0);
+    // not ours to care about
+    let wild = wild_monster(10, pos, velocity);
+    assert_eq!(planner.expected_damage(&wild), 0.0);
+}
+
+#[test]
+fn attacker_plans_control_then_shield() {
+    let hero = hero_at(
+        2,
+        Vec2 {
+            x: 12000.0,
+            y: 6000.0,
+        },
+    );
+    let planner = attacker::Planner {
+        hero: &hero,
+        enemy_base: Vec2::MAX,
+        enemy_heroes: &[],
+        mana: 100,
+        income: 0.0,
+    };
+    let monster = wild_monster(
+        9,
+        Vec2 {
+            x: 12500.0,
+            y: 6000.0,
+        },
+        Vec2 { x: 0.0, y: -400.0 },
+    );
+    let monster = Monster { hp: 40, ..monster };
+    let plan = planner
+        .best_plan(&[monster], attacker::Planner::MAX_TURNS)
+        .unwrap();
+    assert_eq!(plan.monster, 9);
+    assert_eq!(
+        plan.spells,
+        [attacker::Spell::Control, attacker::Spell::Shield]
+    );
+    // controlled towards the enemy base on the first turn, shielded on the
+    // second one, walking a step towards the base on each
+    assert_eq!(plan.actions.len(), 2);
+    assert!(matches!(
+        plan.actions[0],
+        HeroAction::Control { id: 9, target, .. } if target == Vec2::MAX
+    ));
+    assert!(matches!(plan.actions[1], HeroAction::Shield { id: 9, .. }));
+    let step = (Vec2::MAX - monster.pos).normalize() * Monster::SPEED;
+    assert!(plan.end.distance(&(monster.pos + step * 2.0)) < 1.0);
+    // a single spell when that is all there is mana for
+    let poor = attacker::Planner {
+        mana: Hero::SPELL_COST,
+        ..planner
+    };
+    let plan = poor
+        .best_plan(&[monster], attacker::Planner::MAX_TURNS)
+        .unwrap();
+    assert_eq!(plan.actions.len(), 1);
+    assert!(matches!(
+        plan.actions[0],
+        HeroAction::Control { id: 9, target, .. } if target == Vec2::MAX
+    ));
+    assert!(plan.end.distance(&(monster.pos + step)) < 1.0);
+    assert!(plan.damage_per_mana() > 0.0);
+    // the greedy loop doing the first step alone does worse
+    assert!(planner.score(&plan.actions[0], &[monster]) <= plan.damage_per_mana());
+}
+
+#[test]
+fn enemy_tracker_roles_and_velocity() {
+    let mut enemies = enemy::EnemyTracker::default();
+    enemies.next_turn();
+    // enemy base is the far corner, one hero at home and one near ours
+    enemies.observe(
+        3,
+        Vec2 {
+            x: 16000.0,
+            y: 8000.0,
+        },
+        &Vec2::MAX,
+    );
+    enemies.observe(
+        4,
+        Vec2 {
+            x: 4000.0,
+            y: 3000.0,
+        },
+        &Vec2::MAX,
+    );
+    enemies.next_turn();
+    enemies.observe(
+        4,
+        Vec2 {
+            x: 3200.0,
+            y: 3000.0,
+        },
+        &Vec2::MAX,
+    );
+    let heroes = enemies.recent().collect::<Vec<_>>();
+    assert_eq!(heroes[0].role, enemy::Role::Defender);
+    assert_eq!(heroes[1].role, enemy::Role::Attacker);
+    assert_eq!(heroes[1].velocity, Vec2 { x: -800.0, y: 0.0 });
+    assert_eq!(
+        heroes[1].next_pos(),
+        Vec2 {
+            x: 2400.0,
+            y: 3000.0
+        }
+    );
+    // the hero at home hasn't been seen for too long
+    enemies.next_turn();
+    enemies.next_turn();
+    let heroes = enemies.recent().collect::<Vec<_>>();
+    assert_eq!(heroes.len(), 1);
+    assert_eq!(heroes[0].role, enemy::Role::Attacker);
+}
+
+#[test]
+fn enemy_tracker_predicts_spells() {
+    let mut enemies = enemy::EnemyTracker::default();
+    enemies.next_turn();
+    enemies.observe(
+        3,
+        Vec2 {
+            x: 4000.0,
+            y: 3000.0,
+        },
+        &Vec2::MAX,
+    );
+    enemies.next_turn();
+    enemies.observe(
+        3,
+        Vec2 {
+            x: 3200.0,
+            y: 3000.0,
+        },
+        &Vec2::MAX,
+    );
+    // reachable next turn only thanks to the velocity
+    assert!(enemies.can_control(&Vec2 {
+        x: 500.0,
+        y: 3000.0
+    }));
+    assert!(!enemies.can_control(&Vec2 { x: 0.0, y: 0.0 }));
+    let near = base_monster(
+        10,
+        Vec2 {
+            x: 2000.0,
+            y: 2000.0,
+        },
+        10,
+    );
+    let far = base_monster(
+        11,
+        Vec2 {
+            x: 300.0,
+            y: 7000.0,
+        },
+        10,
+    );
+    assert!(enemies.threatens(&near));
+    assert!(!enemies.threatens(&far));
+    // old sightings are forgotten
+    for _ in 0..3 {
+        enemies.next_turn();
+    }
+    assert!(!enemies.threatens(&near));
+}
+
+#[test]
+fn defender_shields_against_control() {
+    let mut enemies = enemy::EnemyTracker::default();
+    enemies.next_turn();
+    let mut hero = hero_at(
+        0,
+        Vec2 {
+            x: 1500.0,
+            y: 1500.0,
+        },
+    );
+    let monster = base_monster(
+        10,
+        Vec2 {
+            x: 2500.0,
+            y: 2500.0,
+        },
+        10,
+    );
+    let action = hero.defend(Some(monster), &[monster], &mut [], 100, &enemies);
+    assert!(!matches!(action, HeroAction::Shield { .. }));
+    enemies.observe(
+        3,
+        Vec2 {
+            x: 2500.0,
+            y: 1500.0,
+        },
+        &Vec2::MAX,
+    );
+    let action = hero.defend(Some(monster), &[monster], &mut [], 100, &enemies);
+    assert!(matches!(action, HeroAction::Shield { id: 0, .. }));
+    // not worth it with little mana
+    let action = hero.defend(Some(monster), &[monster], &mut [], 20, &enemies);
+    assert!(!matches!(action, HeroAction::Shield { .. }));
+}
+
+/// A monster already on the base gives no direction to wait along
+#[test]
+fn defender_waits_on_the_base() {
+    let mut enemies = enemy::EnemyTracker::default();
+    enemies.next_turn();
+    enemies.observe(3, Vec2 { x: 1000.0, y: 0.0 }, &Vec2::MAX);
+    let mut hero = hero_at(0, Vec2 { x: 4000.0, y: 0.0 });
+    let monster = Monster::new(
+        10,
+        Vec2::ZERO,
+        0,
+        false,
+        10,
+        Vec2::ZERO,
+        Some(Vec2::ZERO),
+        true,
+    );
+    let action = hero.defend(Some(monster), &[monster], &mut [], 0, &enemies);
+    assert!(matches!(action, HeroAction::Move { target, .. } if target == Vec2::ZERO));
+}
+
+#[test]
+fn clock_budget_and_overruns() {
+    use std::time::Duration;
+    let ms = Duration::from_millis;
+    let mut clock = clock::TurnClock::new(ms(40), ms(20), ms(5));
+    clock.start();
+    assert_eq!(clock.budget(), ms(40));
+    assert!(clock.remaining() <= ms(35));
+    clock.finish();
+    clock.start();
+    assert_eq!(clock.budget(), ms(20));
+    std::thread::sleep(ms(25));
+    assert!(clock.should_stop());
+    assert!(clock.finish() >= ms(25));
+    assert!(clock.to_string().starts_with("2 turns"));
+    assert!(clock.to_string().ends_with("0 in margin, 1 overruns"));
+}
+
+/// Clock of the tests, only moves when told to
+#[derive(Debug, Clone, Default)]
+struct FakeTime(std::rc::Rc<std::cell::Cell<std::time::Duration>>);
+
+impl FakeTime {
+    fn advance(&self, by: std::time::Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl clock::Time for FakeTime {
+    fn now(&self) -> std::time::Duration {
+        self.0.get()
+    }
+}
+
+#[test]
+fn clock_deepens_until_time_runs_out() {
+    use std::time::Duration;
+    let ms = Duration::from_millis;
+    let time = FakeTime::default();
+    let mut clock = clock::TurnClock::with_time(time.clone(), ms(60), ms(60), ms(10));
+    clock.start();
+    // each depth takes twice as long as the one before: 2 + 4 + 8 + 16 ms
+    // are spent, depth 5 would need 32 and only 20 are left
+    let (depth, reached) = clock
+        .deepen(10, |d| {
+            time.advance(ms(1 << d));
+            d
+        })
+        .unwrap();
+    assert_eq!((depth, reached), (4, 4));
+    assert_eq!(clock.elapsed(), ms(30));
+    // quick steps go all the way
+    let mut clock = clock::TurnClock::default();
+    clock.start();
+    assert_eq!(clock.deepen(5, |d| d * 2), Some((10, 5)));
+    // no time left, no result
+    let mut clock = clock::TurnClock::new(ms(1), ms(1), ms(1));
+    clock.start();
+    assert_eq!(clock.deepen(5, |d| d), None);
+}
+
+/// Opening of a local match, the bot has to keep making the same decisions
+#[test]
+fn replay_opening() {
+    let mut console = Console::replay(include_str!("../replays/opening.txt"));
+    let mut game = Game::new(&mut console).unwrap();
+    // deep enough for any plan, whatever the speed of the machine
+    let mut clock = clock::TurnClock::new(
+        std::time::Duration::from_secs(10),
+        std::time::Duration::from_secs(10),
+        std::time::Duration::ZERO,
+    );
+    while game.update(&mut console, &mut clock).unwrap() {
+        let actions = game.play(&clock);
+        let mut commands = Vec::new();
+        action::write_actions(&mut commands, &actions).unwrap();
+        let commands = String::from_utf8(commands).unwrap();
+        assert!(console.write_turn(&commands), "turn {}", clock.turn());
+    }
+    assert_eq!(clock.turn(), 40);
+}
+
+#[test]
+fn entity_line() {
+    let mut words = Words::new(5, "37 0  6000 4500 2 1 14 -283 -283\t1 1\r\n");
+    let entity = Entity::from_line(&mut words).unwrap();
+    assert_eq!(entity.pos, Vec2::new(6000.0, 4500.0));
+    assert_eq!(entity.velocity, Vec2::new(-283.0, -283.0));
+    assert!(entity.is_controlled && entity.near_base);
+    assert_eq!((entity.id, entity.health, entity.threat_for), (37, 14, 1));
+    // heroes have no target
+    let mut words = Words::new(6, "0 1 1414 849 0 0 -1 -1 -1 -1 -1");
+    assert!(!Entity::from_line(&mut words).unwrap().near_base);
+    let mut words = Words::new(7, "3 4 0 0 0 0 10 0 0 0 0");
+    let error = Entity::from_line(&mut words).unwrap_err();
+    assert_eq!((error.field, error.line_number), ("type", 7));
+    let mut words = Words::new(8, "3 0 100 200 0 0 10");
+    assert_eq!(Entity::from_line(&mut words).unwrap_err().field, "vx");
+}
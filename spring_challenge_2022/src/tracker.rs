@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use crate::{Hero, MapCorner, Monster, Vec2};
+
+/// Last known state of a monster
+#[derive(Debug, Clone, Copy)]
+pub struct Tracked {
+    pub monster: Monster,
+    pub threat_for: i32,
+    pub last_seen: u32,
+}
+
+impl Tracked {
+    pub fn is_visible(&self, turn: u32) -> bool {
+        self.last_seen == turn
+    }
+}
+
+/// Remembers monsters that went out of sight and keeps moving them until
+/// they are proven gone
+#[derive(Debug, Clone, Default)]
+pub struct MonsterTracker {
+    turn: u32,
+    monsters: BTreeMap<i32, Tracked>,
+}
+
+impl MonsterTracker {
+    const BASE_VIEW_RANGE: f32 = cg_common::spring2022::BASE_VIEW_RANGE;
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Starts a new turn, everything observed after this counts as visible
+    pub fn next_turn(&mut self) {
+        self.turn += 1;
+    }
+
+    pub fn observe(&mut self, monster: Monster, threat_for: i32) {
+        self.monsters.insert(
+            monster.id,
+            Tracked {
+                monster,
+                threat_for,
+                last_seen: self.turn,
+            },
+        );
+    }
+
+    /// Extrapolates monsters that were not observed this turn and forgets
+    /// the ones that left the map, hit a base or should be visible but aren't
+    pub fn update(&mut self, heroes: &[Hero], base: &Vec2) {
+        let turn = self.turn;
+        self.monsters.retain(|_, t| {
+            if t.is_visible(turn) {
+                return true;
+            }
+            t.monster.simulate_move();
+            t.monster.eta();
+            let pos = t.monster.pos;
+            let in_sight = pos.distance(base) <= Self::BASE_VIEW_RANGE
+                || heroes
+                    .iter()
+                    .any(|h| h.pos.distance(&pos) <= Hero::VIEW_RANGE);
+            let hit_base = [*base, base.opposite_corner()]
+                .iter()
+                .any(|b| b.distance(&pos) <= Monster::BASE_DAMAGE_RANGE);
+            !t.monster.despawned() && !in_sight && !hit_base
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Tracked> {
+        self.monsters.values()
+    }
+}
@@ -2,16 +2,9 @@ use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Display;
 use std::hash::Hash;
-use std::io;
 use std::ops::Add;
 
-macro_rules! parse_input {
-    ($x:expr, $t:ident) => {
-        $x.trim().parse::<$t>().unwrap()
-    };
-}
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 struct StateHash {
     hash: i32,
 }
@@ -31,29 +24,8 @@ impl From<State> for StateHash {
     }
 }
 
-impl From<&State> for StateHash {
-    fn from(value: &State) -> Self {
-        let mut hash: i32 = 0;
-        for i in 0..9 {
-            hash = hash * 10 + value.tiles[i] as i32;
-        }
-        let new = Self { hash };
-        let mut ret = Self::default();
-        for _ in 0..value.cnt {
-            ret = ret + new;
-        }
-        ret
-    }
-}
-
-impl Default for StateHash {
-    fn default() -> Self {
-        Self { hash: 0 }
-    }
-}
-
 const HASHMOD: i32 = 1 << 30;
-const BITWISE_HASHMOD: i32 = 0b111111111111111111111111111111;
+const BITWISE_HASHMOD: i32 = HASHMOD - 1;
 
 impl Add<StateHash> for StateHash {
     type Output = Self;
@@ -70,10 +42,6 @@ impl Display for StateHash {
     }
 }
 
-struct Tile {
-    val: u8,
-}
-
 #[derive(Debug, Clone, Copy, Eq)]
 struct State {
     tiles: [u8; 9],
@@ -90,15 +58,6 @@ impl Default for State {
 }
 
 impl State {
-    const NGBS_0: [u8; 2] = [1, 3];
-    const NGBS_1: [u8; 3] = [0, 2, 4];
-    const NGBS_2: [u8; 2] = [1, 5];
-    const NGBS_3: [u8; 3] = [0, 4, 6];
-    const NGBS_4: [u8; 4] = [1, 3, 5, 7];
-    const NGBS_5: [u8; 3] = [2, 4, 8];
-    const NGBS_6: [u8; 2] = [3, 7];
-    const NGBS_7: [u8; 3] = [4, 6, 8];
-    const NGBS_8: [u8; 2] = [5, 7];
     const NGBS_0_PERMS: [&[usize]; 1] = [&[1, 3]];
     const NGBS_1_PERMS: [&[usize]; 4] = [&[0, 2], &[0, 4], &[2, 4], &[0, 2, 4]];
     const NGBS_2_PERMS: [&[usize]; 1] = [&[1, 5]];
@@ -131,19 +90,6 @@ impl State {
         &Self::NGBS_7_PERMS,
         &Self::NGBS_8_PERMS,
     ];
-    fn from_io() -> Self {
-        let mut tiles: [u8; 9] = [0; 9];
-        for i in 0..3 as usize {
-            let mut inputs = String::new();
-            io::stdin().read_line(&mut inputs).unwrap();
-            for (j, txt) in inputs.split_whitespace().enumerate() {
-                let value = parse_input!(txt, u8);
-                tiles[i * 3 + j] = value;
-            }
-        }
-        Self { tiles, cnt: 1 }
-    }
-
     fn try_capture(&self, placement: usize, ngbs: &[usize]) -> Option<Self> {
         let mut ttl = 0;
         for ngb in ngbs {
@@ -171,20 +117,13 @@ impl State {
                 results.push(new);
             }
         }
-        if results.len() == 0 {
+        if results.is_empty() {
             let mut new = *self;
             new.tiles[placement] = 1;
             results.push(new);
         }
     }
 
-    fn solve2(&self, depth: i32) -> StateHash {
-        if depth == 0 {
-            return StateHash::from(self);
-        }
-        StateHash::default()
-    }
-
     fn solve(&self, depth: i32) -> i32 {
         let mut stack_a: HashSet<State> = HashSet::with_capacity(1000000);
         let mut stack_b: HashSet<State> = HashSet::with_capacity(1000000);
@@ -242,9 +181,7 @@ impl State {
                     res_count += state.cnt;
                 }
             }
-            let tmp = current;
-            current = new;
-            new = tmp;
+            std::mem::swap(&mut current, &mut new);
         }
         // append the state att current depth, but not finished games
         for state in current.iter() {
@@ -270,12 +207,6 @@ impl State {
     //toggle = true
     //continue
     //if toggle false, record state (hash)
-
-    fn eprint(&self) {
-        eprintln!("{} | {} | {}", self.tiles[0], self.tiles[1], self.tiles[2]);
-        eprintln!("{} | {} | {}", self.tiles[3], self.tiles[4], self.tiles[5]);
-        eprintln!("{} | {} | {}", self.tiles[6], self.tiles[7], self.tiles[8]);
-    }
 }
 
 impl fmt::Display for State {
@@ -342,8 +273,6 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use std::i32;
-
     use super::*;
     #[test]
     fn hash() {